@@ -0,0 +1,295 @@
+use std::{cmp::Ordering, fmt, slice};
+
+// a half-open range of numbers from start up to but not including end. the bounds are u128 so an
+// interval of u64s can end one past u64::MAX and still hold it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    start: u128,
+    end: u128,
+}
+
+impl Interval {
+    // an end before the start gives an empty interval
+    pub fn new(start: u128, end: u128) -> Self {
+        Self {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    // the interval of length numbers from start, cut off at u128::MAX if it is too long
+    pub fn with_len(start: u128, len: u128) -> Self {
+        Self::new(start, start.saturating_add(len))
+    }
+
+    pub fn start(&self) -> u128 {
+        self.start
+    }
+
+    pub fn end(&self) -> u128 {
+        self.end
+    }
+
+    pub fn len(&self) -> u128 {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, n: u128) -> bool {
+        self.start <= n && n < self.end
+    }
+
+    // the numbers in both intervals, which is empty if they don't overlap
+    pub fn intersection(&self, other: &Interval) -> Interval {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // moves the interval up or down by offset, cutting off anything that would go below 0
+    pub fn shift(&self, offset: i128) -> Interval {
+        Self::new(
+            self.start.saturating_add_signed(offset),
+            self.end.saturating_add_signed(offset),
+        )
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.start, self.end).cmp(&(other.start, other.end))
+    }
+}
+
+// a set of numbers stored as intervals, which are always kept sorted with no empty intervals and
+// with any overlapping or touching intervals merged together
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        // every interval that overlaps or touches the new one gets merged into it
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, i| {
+                Interval::new(merged.start.min(i.start), merged.end.max(i.end))
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // the lowest number in the set
+    pub fn min(&self) -> Option<u128> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn contains(&self, n: u128) -> bool {
+        let i = self.intervals.partition_point(|i| i.start <= n);
+        i.checked_sub(1)
+            .is_some_and(|i| self.intervals[i].contains(n))
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = IntervalSet::new();
+        let (mut a, mut b) = (self.iter().peekable(), other.iter().peekable());
+        // walk through both sets together, moving on from whichever interval ends first
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            result.insert(x.intersection(y));
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        result
+    }
+
+    // everything within bounds that isn't in the set
+    pub fn complement(&self, bounds: Interval) -> IntervalSet {
+        let mut result = IntervalSet::new();
+        let mut start = bounds.start;
+        for i in &self.intervals {
+            result.insert(Interval::new(start, i.start.min(bounds.end)));
+            start = start.max(i.end);
+        }
+        result.insert(Interval::new(start, bounds.end));
+        result
+    }
+
+    // the numbers in this set that aren't in other
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => {
+                self.intersection(&other.complement(Interval::new(first.start, last.end)))
+            }
+            _ => IntervalSet::new(),
+        }
+    }
+
+    pub fn shift(&self, offset: i128) -> IntervalSet {
+        self.iter().map(|i| i.shift(offset)).collect()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut intervals: Vec<Interval> = iter.into_iter().filter(|i| !i.is_empty()).collect();
+        intervals.sort();
+        let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+        for i in intervals {
+            match merged.last_mut() {
+                Some(last) if i.start <= last.end => last.end = last.end.max(i.end),
+                _ => merged.push(i),
+            }
+        }
+        Self { intervals: merged }
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        [interval].into_iter().collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a IntervalSet {
+    type Item = &'a Interval;
+    type IntoIter = slice::Iter<'a, Interval>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(u128, u128)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|(s, e)| Interval::new(*s, *e))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::with_len(55, 13);
+        assert_eq!(Interval::new(55, 68), interval);
+        assert_eq!(13, interval.len());
+        assert!(interval.contains(55));
+        assert!(interval.contains(67));
+        assert!(!interval.contains(68));
+        assert!(Interval::new(10, 5).is_empty());
+        // u64::MAX fits in an interval of u64s
+        let top = Interval::with_len(u64::MAX as u128, 1);
+        assert!(top.contains(u64::MAX as u128));
+        assert_eq!(1 << 64, top.end());
+        assert_eq!(u128::MAX, Interval::with_len(u128::MAX - 1, 5).end());
+        assert_eq!("55..68", interval.to_string());
+
+        assert_eq!(
+            Interval::new(60, 68),
+            interval.intersection(&Interval::new(60, 100))
+        );
+        assert!(interval.intersection(&Interval::new(0, 10)).is_empty());
+
+        assert_eq!(Interval::new(57, 70), interval.shift(2));
+        assert_eq!(Interval::new(0, 8), interval.shift(-60));
+        assert_eq!(
+            Interval::new(u64::MAX as u128 + 55, u64::MAX as u128 + 68),
+            interval.shift(u64::MAX as i128)
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            vec![Interval::new(0, 10), Interval::new(12, 15)],
+            set(&[(5, 10), (12, 15), (0, 6), (8, 8), (3, 4)]).intervals()
+        );
+        // touching intervals are merged
+        assert_eq!(set(&[(0, 20)]), set(&[(10, 20), (0, 10)]));
+
+        let mut s = set(&[(0, 5), (10, 15), (20, 25)]);
+        s.insert(Interval::new(4, 10));
+        assert_eq!(set(&[(0, 15), (20, 25)]), s);
+        s.insert(Interval::new(16, 18));
+        assert_eq!(set(&[(0, 15), (16, 18), (20, 25)]), s);
+        s.insert(Interval::new(30, 30));
+        assert_eq!(3, s.iter().count());
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = set(&[(0, 5), (10, 15)]);
+        assert!(s.contains(0));
+        assert!(s.contains(14));
+        assert!(!s.contains(5));
+        assert!(!s.contains(20));
+        assert_eq!(Some(0), s.min());
+        assert_eq!(None, IntervalSet::new().min());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (40, 50)]);
+        assert_eq!(set(&[(0, 30), (40, 50)]), a.union(&b));
+        assert_eq!(set(&[(5, 10), (20, 25)]), a.intersection(&b));
+        assert_eq!(set(&[(0, 5), (25, 30)]), a.difference(&b));
+        assert_eq!(
+            set(&[(10, 20), (30, 60)]),
+            a.complement(Interval::new(5, 60))
+        );
+        assert_eq!(
+            set(&[(30, 40), (50, 60)]),
+            a.union(&b).complement(Interval::new(5, 60))
+        );
+        assert_eq!(
+            set(&[(0, 100)]),
+            IntervalSet::new().complement(Interval::new(0, 100))
+        );
+        assert_eq!(IntervalSet::new(), IntervalSet::new().difference(&a));
+        assert_eq!(set(&[(10, 20), (30, 40)]), a.shift(10));
+    }
+}
@@ -0,0 +1,386 @@
+use std::{
+    env,
+    error::Error,
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor},
+    path::{Path, PathBuf},
+};
+
+mod interval;
+mod solution;
+mod span;
+
+pub use interval::{Interval, IntervalSet};
+pub use solution::Solution;
+pub use span::Span;
+
+#[derive(Debug)]
+pub enum InputError {
+    // the input file could not be opened
+    Open {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // reading from an already opened input failed
+    Read {
+        line: usize,
+        source: io::Error,
+    },
+    // the command line arguments did not describe a valid input
+    InvalidArgs(String),
+    // a grid row was a different width to the first row
+    RaggedGrid {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open { path, source } => {
+                write!(f, "could not open {}: {source}", path.display())
+            }
+            Self::Read { line, source } => write!(f, "could not read line {line}: {source}"),
+            Self::InvalidArgs(message) => write!(f, "{message}\n{USAGE}"),
+            Self::RaggedGrid {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "grid row on line {line} has {found} columns, expected {expected}"
+            ),
+        }
+    }
+}
+
+// io errors can't be compared, so compare their kinds instead. this lets each day's error type
+// derive PartialEq for use in tests
+impl PartialEq for InputError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Open { path, source },
+                Self::Open {
+                    path: other_path,
+                    source: other_source,
+                },
+            ) => path == other_path && source.kind() == other_source.kind(),
+            (
+                Self::Read { line, source },
+                Self::Read {
+                    line: other_line,
+                    source: other_source,
+                },
+            ) => line == other_line && source.kind() == other_source.kind(),
+            (Self::InvalidArgs(message), Self::InvalidArgs(other_message)) => {
+                message == other_message
+            }
+            (
+                Self::RaggedGrid {
+                    line,
+                    expected,
+                    found,
+                },
+                Self::RaggedGrid {
+                    line: other_line,
+                    expected: other_expected,
+                    found: other_found,
+                },
+            ) => line == other_line && expected == other_expected && found == other_found,
+            _ => false,
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Open { source, .. } | Self::Read { source, .. } => Some(source),
+            Self::InvalidArgs(_) | Self::RaggedGrid { .. } => None,
+        }
+    }
+}
+
+// opens a file for buffered reading, where a path of "-" reads from stdin instead
+pub fn open<P: AsRef<Path>>(path: P) -> Result<Box<dyn BufRead>, InputError> {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    match File::open(path) {
+        Ok(file) => Ok(Box::new(BufReader::new(file))),
+        Err(source) => Err(InputError::Open {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+const USAGE: &str = "Usage: <part> [<input file> | - | --test | --inline <input>]";
+
+#[derive(Debug, PartialEq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    Inline(String),
+}
+
+impl InputSource {
+    // works out where a part binary should read from, where crate_dir is the directory of the
+    // day's crate so the default input.txt and --test's test.txt are found from anywhere
+    pub fn from_args<I>(args: I, crate_dir: &Path) -> Result<Self, InputError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        let source = match args.next().as_deref() {
+            None => Self::File(crate_dir.join("input.txt")),
+            Some("--test") => Self::File(crate_dir.join("test.txt")),
+            Some("--inline") => match args.next() {
+                Some(input) => Self::Inline(input),
+                None => {
+                    return Err(InputError::InvalidArgs(String::from(
+                        "No input given after --inline",
+                    )))
+                }
+            },
+            Some("-") => Self::Stdin,
+            Some(arg) if arg.starts_with("--") => {
+                return Err(InputError::InvalidArgs(format!("Unknown argument: {arg}")))
+            }
+            Some(path) => Self::File(PathBuf::from(path)),
+        };
+        match args.next() {
+            Some(extra) => Err(InputError::InvalidArgs(format!(
+                "Unexpected argument: {extra}"
+            ))),
+            None => Ok(source),
+        }
+    }
+
+    pub fn open(&self) -> Result<Box<dyn BufRead>, InputError> {
+        match self {
+            Self::File(path) => open(path),
+            Self::Stdin => Ok(Box::new(io::stdin().lock())),
+            Self::Inline(input) => Ok(Box::new(Cursor::new(input.clone().into_bytes()))),
+        }
+    }
+}
+
+// opens whichever input the current process' arguments ask for, used by the part binaries as
+// open_from_args(env!("CARGO_MANIFEST_DIR"))
+pub fn open_from_args<P: AsRef<Path>>(crate_dir: P) -> Result<Box<dyn BufRead>, InputError> {
+    InputSource::from_args(env::args().skip(1), crate_dir.as_ref())?.open()
+}
+
+pub fn read_lines<R: BufRead>(reader: R) -> Result<Vec<String>, InputError> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.map_err(|source| InputError::Read {
+                line: i + 1,
+                source,
+            })
+        })
+        .collect()
+}
+
+// a group of consecutive non-blank lines from the input
+#[derive(Debug, PartialEq, Eq)]
+pub struct Block {
+    // line number of the first line in the block, counted from 1
+    pub first_line: usize,
+    pub lines: Vec<String>,
+}
+
+impl Block {
+    // each line of the block along with its line number in the input
+    pub fn numbered(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (self.first_line + i, l.as_str()))
+    }
+}
+
+// splits the input into blocks of lines separated by one or more blank lines
+pub fn read_blocks<R: BufRead>(reader: R) -> Result<Vec<Block>, InputError> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut first_line = 1;
+    for (i, line) in read_lines(reader)?.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(Block {
+                    first_line,
+                    lines: current,
+                });
+                current = Vec::new();
+            }
+        } else {
+            if current.is_empty() {
+                first_line = i + 1;
+            }
+            current.push(line);
+        }
+    }
+    // last block usually has no blank line after it
+    if !current.is_empty() {
+        blocks.push(Block {
+            first_line,
+            lines: current,
+        });
+    }
+    Ok(blocks)
+}
+
+// reads a rectangular grid of characters, ignoring any blank lines at the end
+pub fn read_grid<R: BufRead>(reader: R) -> Result<Vec<Vec<char>>, InputError> {
+    let mut lines = read_lines(reader)?;
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+
+    let mut grid: Vec<Vec<char>> = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        let row: Vec<char> = line.chars().collect();
+        if let Some(first) = grid.first() {
+            if row.len() != first.len() {
+                return Err(InputError::RaggedGrid {
+                    line: i + 1,
+                    expected: first.len(),
+                    found: row.len(),
+                });
+            }
+        }
+        grid.push(row);
+    }
+    Ok(grid)
+}
+
+pub fn read_string<R: BufRead>(mut reader: R) -> Result<String, InputError> {
+    let mut input = String::new();
+    let mut line = 1;
+    loop {
+        match reader.read_line(&mut input) {
+            Ok(0) => return Ok(input),
+            Ok(_) => line += 1,
+            Err(source) => return Err(InputError::Read { line, source }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_lines() {
+        let input = "0 3 6\n1 3 6\n\n10 13 16\n";
+        assert_eq!(
+            vec!["0 3 6", "1 3 6", "", "10 13 16"],
+            read_lines(input.as_bytes()).unwrap()
+        );
+        assert_eq!(Vec::<String>::new(), read_lines("".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_read_blocks() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\nsoil-to-fertilizer map:\n0 15 37";
+        let blocks = read_blocks(input.as_bytes()).unwrap();
+        assert_eq!(
+            vec![
+                vec!["seeds: 79 14"],
+                vec!["seed-to-soil map:", "50 98 2", "52 50 48"],
+                vec!["soil-to-fertilizer map:", "0 15 37"],
+            ],
+            blocks.iter().map(|b| b.lines.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(3, "seed-to-soil map:"), (4, "50 98 2"), (5, "52 50 48")],
+            blocks[1].numbered().collect::<Vec<_>>()
+        );
+        assert_eq!(8, blocks[2].first_line);
+    }
+
+    #[test]
+    fn test_read_grid() {
+        let input = "#.#\n.#.\n\n";
+        assert_eq!(
+            vec![vec!['#', '.', '#'], vec!['.', '#', '.']],
+            read_grid(input.as_bytes()).unwrap()
+        );
+
+        let ragged = "#.#\n.#\n";
+        match read_grid(ragged.as_bytes()) {
+            Err(InputError::RaggedGrid {
+                line,
+                expected,
+                found,
+            }) => assert_eq!((2, 3, 2), (line, expected, found)),
+            other => panic!("expected ragged grid error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_read_string() {
+        let input = "Time:      7  15   30\nDistance:  9  40  200";
+        assert_eq!(input, read_string(input.as_bytes()).unwrap());
+    }
+
+    fn args(input: &[&str]) -> Vec<String> {
+        input.iter().map(|a| String::from(*a)).collect()
+    }
+
+    #[test]
+    fn test_input_source_from_args() {
+        let dir = Path::new("day-05");
+        assert_eq!(
+            InputSource::File(PathBuf::from("day-05/input.txt")),
+            InputSource::from_args(args(&[]), dir).unwrap()
+        );
+        assert_eq!(
+            InputSource::File(PathBuf::from("day-05/test.txt")),
+            InputSource::from_args(args(&["--test"]), dir).unwrap()
+        );
+        assert_eq!(
+            InputSource::File(PathBuf::from("other/input.txt")),
+            InputSource::from_args(args(&["other/input.txt"]), dir).unwrap()
+        );
+        assert_eq!(
+            InputSource::Stdin,
+            InputSource::from_args(args(&["-"]), dir).unwrap()
+        );
+        assert_eq!(
+            InputSource::Inline(String::from("0 3 6\n1 3 6")),
+            InputSource::from_args(args(&["--inline", "0 3 6\n1 3 6"]), dir).unwrap()
+        );
+
+        assert!(InputSource::from_args(args(&["--inline"]), dir).is_err());
+        assert!(InputSource::from_args(args(&["--tset"]), dir).is_err());
+        assert!(InputSource::from_args(args(&["a.txt", "b.txt"]), dir).is_err());
+    }
+
+    #[test]
+    fn test_open_inline() {
+        let input = InputSource::Inline(String::from("1 2\n3 4"))
+            .open()
+            .unwrap();
+        assert_eq!(vec!["1 2", "3 4"], read_lines(input).unwrap());
+    }
+
+    #[test]
+    fn test_open_missing_file() {
+        match open("does-not-exist.txt") {
+            Err(InputError::Open { path, .. }) => {
+                assert_eq!(PathBuf::from("does-not-exist.txt"), path)
+            }
+            _ => panic!("expected open error"),
+        }
+    }
+}
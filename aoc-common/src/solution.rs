@@ -0,0 +1,17 @@
+use std::{error::Error, fmt, io::BufRead};
+
+// common interface for every day, so the runner and other tooling can treat them all the same
+pub trait Solution {
+    const DAY: u32;
+
+    // the parsed puzzle input shared by both parts
+    type Input;
+    type Answer: fmt::Display;
+    type Error: Error;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, Self::Error>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer, Self::Error>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer, Self::Error>;
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+
+// where some offending text was found in the input, with lines and columns counted from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize, text: &str) -> Self {
+        Self {
+            line,
+            column,
+            text: String::from(text),
+        }
+    }
+
+    // span of `part`, which should be a slice of `line`, e.g. one of the pieces from
+    // `line.split(' ')`. parsers for a single line don't know which line they are on, so this
+    // starts at line 1 and the caller can move it with `on_line`
+    pub fn of(line: &str, part: &str) -> Self {
+        let start = line.as_ptr() as usize;
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|o| o + part.len() <= line.len() && line.is_char_boundary(*o))
+            .unwrap_or(0);
+        let column = line[..offset].chars().count() + 1;
+        Self::new(1, column, part)
+    }
+
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "\"{}\" at line {}, column {}",
+            self.text, self.line, self.column
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_of() {
+        let line = "Game 12: 3 blue, 4 red";
+        let parts: Vec<&str> = line.split(' ').collect();
+        assert_eq!(Span::new(1, 1, "Game"), Span::of(line, parts[0]));
+        assert_eq!(Span::new(1, 10, "3"), Span::of(line, parts[2]));
+        assert_eq!(Span::new(4, 20, "red"), Span::of(line, parts[5]).on_line(4));
+        // not part of the line, so the best we can do is point at the start
+        let other = String::from("other");
+        assert_eq!(Span::new(1, 1, "other"), Span::of(line, &other));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            "\"purple\" at line 3, column 14",
+            Span::new(3, 14, "purple").to_string()
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use std::{fs, hint::black_box, path::PathBuf};
+
+use aoc_common::Solution;
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_01::Day01;
+use day_02::Day02;
+use day_05::Day05;
+use day_06::Day06;
+use day_07::Day07;
+use day_09::Day09;
+
+// benchmarks parsing and each part separately for the day's example and real input, e.g. the
+// group day-05/input.txt has parse, part1 and part2 benchmarks. inputs that are missing are
+// skipped, as are parts that fail for an input such as day 1 part 1 on test2.txt
+fn bench_day<S: Solution>(c: &mut Criterion) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(format!("day-{:02}", S::DAY));
+    for name in ["test.txt", "input.txt"] {
+        let Ok(input) = fs::read_to_string(dir.join(name)) else {
+            continue;
+        };
+        let Ok(parsed) = S::parse(input.as_bytes()) else {
+            continue;
+        };
+        let mut group = c.benchmark_group(format!("day-{:02}/{name}", S::DAY));
+        group.bench_function("parse", |b| {
+            b.iter(|| S::parse(black_box(input.as_bytes())))
+        });
+        if S::part1(&parsed).is_ok() {
+            group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&parsed))));
+        }
+        if S::part2(&parsed).is_ok() {
+            group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&parsed))));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(
+    days,
+    bench_day::<Day01>,
+    bench_day::<Day02>,
+    bench_day::<Day05>,
+    bench_day::<Day06>,
+    bench_day::<Day07>,
+    bench_day::<Day09>
+);
+criterion_main!(days);
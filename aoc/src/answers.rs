@@ -0,0 +1,232 @@
+use std::{
+    fmt, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use crate::days::Day;
+
+// the expected answers for one input file. they are stored next to the input with an .answers
+// extension, so day-05/test.answers holds the answers for day-05/test.txt. either part can be
+// missing since some examples only apply to one part
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    pub fn get(&self, part: usize) -> Result<Option<&str>, String> {
+        match part {
+            1 => Ok(self.part1.as_deref()),
+            2 => Ok(self.part2.as_deref()),
+            _ => Err(format!("there is no part {part}")),
+        }
+    }
+
+    pub fn set(&mut self, part: usize, answer: String) -> Result<(), String> {
+        match part {
+            1 => self.part1 = Some(answer),
+            2 => self.part2 = Some(answer),
+            _ => return Err(format!("there is no part {part}")),
+        }
+        Ok(())
+    }
+
+    // each line is "part1: <answer>" or "part2: <answer>", blank lines are ignored
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut answers = Self::default();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let part = match line.split_once(':') {
+                Some(("part1", answer)) => (1, answer),
+                Some(("part2", answer)) => (2, answer),
+                _ => return Err(format!("invalid answer on line {}: {line}", i + 1)),
+            };
+            answers.set(part.0, String::from(part.1.trim()))?;
+        }
+        Ok(answers)
+    }
+
+    // answers recorded for the input, or none if nothing has been recorded yet
+    pub fn load(input: &Path) -> Result<Option<Self>, String> {
+        let path = answers_path(input);
+        match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents)
+                .map(Some)
+                .map_err(|e| format!("{}: {e}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("could not read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn save(&self, input: &Path) -> Result<(), String> {
+        let path = answers_path(input);
+        fs::write(&path, self.to_string())
+            .map_err(|e| format!("could not write {}: {e}", path.display()))
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(answer) = &self.part1 {
+            writeln!(f, "part1: {answer}")?;
+        }
+        if let Some(answer) = &self.part2 {
+            writeln!(f, "part2: {answer}")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn answers_path(input: &Path) -> PathBuf {
+    input.with_extension("answers")
+}
+
+// every input in a day's folder that has answers recorded for it, sorted by name
+pub fn recorded_inputs(day_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        fs::read_dir(day_dir).map_err(|e| format!("could not read {}: {e}", day_dir.display()))?;
+    let mut inputs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "answers"))
+        .map(|p| p.with_extension("txt"))
+        .collect();
+    inputs.sort();
+    Ok(inputs)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Match,
+    Mismatch { expected: String, found: String },
+    // the solution returned an error instead of an answer
+    Failed(String),
+}
+
+pub struct Check {
+    pub input: PathBuf,
+    pub part: usize,
+    pub outcome: Outcome,
+}
+
+// compares one recomputed answer with the recorded one
+pub fn check(expected: &str, found: Result<String, String>) -> Outcome {
+    match found {
+        Ok(found) if found == expected => Outcome::Match,
+        Ok(found) => Outcome::Mismatch {
+            expected: String::from(expected),
+            found,
+        },
+        Err(e) => Outcome::Failed(e),
+    }
+}
+
+// recomputes every answer recorded for the day's inputs
+pub fn verify_day(day: &Day, parts: &[usize]) -> Result<Vec<Check>, String> {
+    let mut checks = vec![];
+    for input in recorded_inputs(&day.dir())? {
+        let Some(answers) = Answers::load(&input)? else {
+            continue;
+        };
+        for part in parts {
+            if let Some(expected) = answers.get(*part)? {
+                checks.push(Check {
+                    input: input.clone(),
+                    part: *part,
+                    outcome: check(expected, day.solve_file(&input, *part)),
+                });
+            }
+        }
+    }
+    Ok(checks)
+}
+
+// works out the answers for the day's input.txt and records them, keeping any recorded answers
+// for parts that weren't asked for
+pub fn record_day(day: &Day, parts: &[usize]) -> Result<Answers, String> {
+    let input = day.input_path();
+    let mut answers = Answers::load(&input)?.unwrap_or_default();
+    for part in parts {
+        answers.set(*part, day.solve_file(&input, *part)?)?;
+    }
+    answers.save(&input)?;
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::DAYS;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse("part1: 35\n\npart2: 46\n").unwrap();
+        assert_eq!(Ok(Some("35")), answers.get(1));
+        assert_eq!(Ok(Some("46")), answers.get(2));
+        assert_eq!("part1: 35\npart2: 46\n", answers.to_string());
+
+        let answers = Answers::parse("part2: 281").unwrap();
+        assert_eq!(Ok(None), answers.get(1));
+        assert_eq!("part2: 281\n", answers.to_string());
+
+        let mut answers = Answers::default();
+        assert!(answers.get(3).is_err());
+        assert!(answers.set(0, String::from("1")).is_err());
+        assert_eq!(Answers::default(), answers);
+
+        assert!(Answers::parse("part3: 1").is_err());
+        assert!(Answers::parse("35").is_err());
+    }
+
+    #[test]
+    fn test_answers_path() {
+        assert_eq!(
+            PathBuf::from("day-05/test.answers"),
+            answers_path(Path::new("day-05/test.txt"))
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(Outcome::Match, check("35", Ok(String::from("35"))));
+        assert_eq!(
+            Outcome::Mismatch {
+                expected: String::from("35"),
+                found: String::from("36")
+            },
+            check("35", Ok(String::from("36")))
+        );
+        assert_eq!(
+            Outcome::Failed(String::from("no seeds")),
+            check("35", Err(String::from("no seeds")))
+        );
+    }
+
+    // the regression suite: every recorded answer, for the examples and any real inputs that
+    // have been recorded locally, must still come out the same
+    #[test]
+    fn test_recorded_answers() {
+        for day in DAYS.iter() {
+            let checks = verify_day(day, &[1, 2]).unwrap();
+            assert!(
+                !checks.is_empty(),
+                "no answers recorded for day {}",
+                day.number
+            );
+            for c in checks {
+                assert_eq!(
+                    Outcome::Match,
+                    c.outcome,
+                    "day {} part {} with {}",
+                    day.number,
+                    c.part,
+                    c.input.display()
+                );
+            }
+        }
+    }
+}
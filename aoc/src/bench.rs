@@ -0,0 +1,112 @@
+use std::{
+    fs,
+    hint::black_box,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use aoc_common::Solution;
+
+// each stage is run at most this many times, but slow stages stop early once they have used up
+// the time limit so a brute force part doesn't take forever
+const MAX_RUNS: usize = 101;
+const TIME_LIMIT: Duration = Duration::from_secs(2);
+
+// median times for one input, where a part is none if it wasn't asked for or failed
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+}
+
+// none if f fails, which is found out on the first run so failures aren't timed
+fn median_time<T, E>(mut f: impl FnMut() -> Result<T, E>) -> Option<Duration> {
+    let start = Instant::now();
+    let mut times = vec![];
+    while times.len() < MAX_RUNS && (times.is_empty() || start.elapsed() < TIME_LIMIT) {
+        let run = Instant::now();
+        let result = black_box(f());
+        times.push(run.elapsed());
+        if result.is_err() {
+            return None;
+        }
+    }
+    times.sort();
+    Some(times[times.len() / 2])
+}
+
+// times parsing and each part separately, with the input already read into memory so reading
+// the file isn't counted
+pub fn time<S: Solution>(input: &str, parts: &[usize]) -> Result<Timings, String> {
+    let parsed = S::parse(input.as_bytes()).map_err(|e| e.to_string())?;
+    let parse = median_time(|| S::parse(black_box(input.as_bytes()))).unwrap_or_default();
+    let time_part = |part: usize| match part {
+        _ if !parts.contains(&part) => None,
+        1 => median_time(|| S::part1(black_box(&parsed))),
+        2 => median_time(|| S::part2(black_box(&parsed))),
+        _ => None,
+    };
+    Ok(Timings {
+        parse,
+        part1: time_part(1),
+        part2: time_part(2),
+    })
+}
+
+// the inputs to benchmark for a day, which is every text file in its folder
+pub fn bench_inputs(day_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        fs::read_dir(day_dir).map_err(|e| format!("could not read {}: {e}", day_dir.display()))?;
+    let mut inputs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    inputs.sort();
+    Ok(inputs)
+}
+
+pub fn table_header() -> String {
+    String::from("| Day | Input | Parse | Part 1 | Part 2 |\n|---|---|---|---|---|")
+}
+
+pub fn table_row(day: u32, input: &str, timings: &Timings) -> String {
+    let part = |t: Option<Duration>| match t {
+        Some(t) => format!("{t:.2?}"),
+        None => String::from("-"),
+    };
+    format!(
+        "| {day:02} | {input} | {:.2?} | {} | {} |",
+        timings.parse,
+        part(timings.part1),
+        part(timings.part2)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day_06::Day06;
+
+    #[test]
+    fn test_time() {
+        let input = "Time:      7  15   30\nDistance:  9  40  200";
+        let timings = time::<Day06>(input, &[2]).unwrap();
+        assert!(timings.part1.is_none());
+        assert!(timings.part2.is_some());
+        assert!(time::<Day06>("Time: x", &[1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_table_row() {
+        let timings = Timings {
+            parse: Duration::from_micros(120),
+            part1: Some(Duration::from_nanos(5100)),
+            part2: None,
+        };
+        assert_eq!(
+            "| 05 | test.txt | 120.00µs | 5.10µs | - |",
+            table_row(5, "test.txt", &timings)
+        );
+    }
+}
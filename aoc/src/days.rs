@@ -0,0 +1,89 @@
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+
+use aoc_common::{open, Solution};
+
+use crate::bench::{time, Timings};
+use day_01::Day01;
+use day_02::Day02;
+use day_05::Day05;
+use day_06::Day06;
+use day_07::Day07;
+use day_09::Day09;
+
+pub type Input = Box<dyn BufRead>;
+
+pub struct Day {
+    pub number: u32,
+    // parses the opened input file and returns the answer for the given part as a string
+    pub solve: fn(Input, usize) -> Result<String, String>,
+    // times parsing and the given parts separately for an input that has already been read
+    pub time: fn(&str, &[usize]) -> Result<Timings, String>,
+}
+
+pub const DAYS: [Day; 6] = [
+    day::<Day01>(),
+    day::<Day02>(),
+    day::<Day05>(),
+    day::<Day06>(),
+    day::<Day07>(),
+    day::<Day09>(),
+];
+
+const fn day<S: Solution>() -> Day {
+    Day {
+        number: S::DAY,
+        solve: solve::<S>,
+        time: time::<S>,
+    }
+}
+
+impl Day {
+    // inputs live next to each day's crate, so find them relative to this one
+    pub fn dir(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join(format!("day-{:02}", self.number))
+    }
+
+    pub fn input_path(&self) -> PathBuf {
+        self.dir().join("input.txt")
+    }
+
+    pub fn solve_file(&self, path: &Path, part: usize) -> Result<String, String> {
+        match open(path) {
+            Ok(input) => (self.solve)(input, part),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+pub fn find_day(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
+
+fn solve<S: Solution>(input: Input, part: usize) -> Result<String, String> {
+    let parsed = S::parse(input).map_err(|e| e.to_string())?;
+    let answer = match part {
+        1 => S::part1(&parsed),
+        2 => S::part2(&parsed),
+        _ => return Err(format!("there is no part {part}")),
+    };
+    answer.map(|a| a.to_string()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let input = || -> Input { Box::new("0 3 6 9 12 15".as_bytes()) };
+        assert_eq!(Ok(String::from("18")), solve::<Day09>(input(), 1));
+        assert_eq!(Ok(String::from("-3")), solve::<Day09>(input(), 2));
+        assert!(solve::<Day09>(input(), 3).is_err());
+        assert!(solve::<Day09>(input(), 0).is_err());
+    }
+}
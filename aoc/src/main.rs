@@ -0,0 +1,243 @@
+use std::{env, fs, process::ExitCode, time::Instant};
+
+mod answers;
+mod bench;
+mod days;
+
+use answers::{record_day, verify_day, Outcome};
+use bench::{bench_inputs, table_header, table_row};
+use days::{find_day, Day, DAYS};
+
+const USAGE: &str = "Usage: aoc <run|verify|record|bench> <day|all> [--part <1|2>]";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    // solve the real inputs and print the answers
+    Run,
+    // check the answers recorded for each input still come out the same
+    Verify,
+    // record the current answers for the real inputs
+    Record,
+    // time parsing and solving every input separately and print a table of the results
+    Bench,
+}
+
+struct RunArgs {
+    command: Command,
+    days: Vec<&'static Day>,
+    parts: Vec<usize>,
+}
+
+fn parse_args(args: &[String]) -> Result<RunArgs, String> {
+    let command = match args.first().map(String::as_str) {
+        Some("run") => Command::Run,
+        Some("verify") => Command::Verify,
+        Some("record") => Command::Record,
+        Some("bench") => Command::Bench,
+        Some(other) => return Err(format!("Unknown command: {other}")),
+        None => return Err(String::from("No command given")),
+    };
+
+    let days: Vec<&'static Day> = match args.get(1).map(String::as_str) {
+        Some("all") => DAYS.iter().collect(),
+        Some(day) => {
+            let number = day
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid day: {day}"))?;
+            match find_day(number) {
+                Some(d) => vec![d],
+                None => return Err(format!("No solution for day {number}")),
+            }
+        }
+        None => return Err(String::from("No day given")),
+    };
+
+    let parts = match args.get(2).map(String::as_str) {
+        Some("--part") => match args.get(3).map(String::as_str) {
+            Some("1") => vec![1],
+            Some("2") => vec![2],
+            Some(p) => return Err(format!("Invalid part: {p}")),
+            None => return Err(String::from("No part given after --part")),
+        },
+        Some(other) => return Err(format!("Unknown argument: {other}")),
+        None => vec![1, 2],
+    };
+
+    Ok(RunArgs {
+        command,
+        days,
+        parts,
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let run_args = match parse_args(&args) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let succeeded = match run_args.command {
+        Command::Run => run(&run_args),
+        Command::Verify => verify(&run_args),
+        Command::Record => record(&run_args),
+        Command::Bench => bench(&run_args),
+    };
+    if succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn run(run_args: &RunArgs) -> bool {
+    let mut failed = false;
+    for day in &run_args.days {
+        let path = day.input_path();
+        for part in &run_args.parts {
+            let start = Instant::now();
+            let result = day.solve_file(&path, *part);
+            let elapsed = start.elapsed();
+            match result {
+                Ok(answer) => println!(
+                    "Day {:02} part {part}: {answer} ({elapsed:.2?})",
+                    day.number
+                ),
+                Err(e) => {
+                    eprintln!("Day {:02} part {part} failed: {e}", day.number);
+                    failed = true;
+                }
+            }
+        }
+    }
+    !failed
+}
+
+fn verify(run_args: &RunArgs) -> bool {
+    let mut checked = 0;
+    let mut failed = 0;
+    for day in &run_args.days {
+        let checks = match verify_day(day, &run_args.parts) {
+            Ok(checks) => checks,
+            Err(e) => {
+                eprintln!("Day {:02} could not be verified: {e}", day.number);
+                failed += 1;
+                continue;
+            }
+        };
+        if checks.is_empty() {
+            println!("Day {:02}: no answers recorded", day.number);
+        }
+        for c in checks {
+            let name = c.input.file_name().unwrap_or_default().to_string_lossy();
+            let label = format!("Day {:02} part {} ({name})", day.number, c.part);
+            checked += 1;
+            match c.outcome {
+                Outcome::Match => println!("{label}: ok"),
+                Outcome::Mismatch { expected, found } => {
+                    println!("{label}: MISMATCH expected {expected}, found {found}");
+                    failed += 1;
+                }
+                Outcome::Failed(e) => {
+                    println!("{label}: FAILED {e}");
+                    failed += 1;
+                }
+            }
+        }
+    }
+    println!("{checked} answers checked, {failed} failed");
+    failed == 0
+}
+
+fn record(run_args: &RunArgs) -> bool {
+    let mut failed = false;
+    for day in &run_args.days {
+        match record_day(day, &run_args.parts) {
+            Ok(answers) => {
+                for part in &run_args.parts {
+                    let answer = answers.get(*part).ok().flatten().unwrap_or_default();
+                    println!("Day {:02} part {part}: recorded {answer}", day.number);
+                }
+            }
+            Err(e) => {
+                eprintln!("Day {:02} could not be recorded: {e}", day.number);
+                failed = true;
+            }
+        }
+    }
+    !failed
+}
+
+fn bench(run_args: &RunArgs) -> bool {
+    let mut failed = false;
+    println!("{}", table_header());
+    for day in &run_args.days {
+        let inputs = match bench_inputs(&day.dir()) {
+            Ok(inputs) => inputs,
+            Err(e) => {
+                eprintln!("Day {:02} could not be benchmarked: {e}", day.number);
+                failed = true;
+                continue;
+            }
+        };
+        for path in inputs {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let result = fs::read_to_string(&path)
+                .map_err(|e| format!("could not read {}: {e}", path.display()))
+                .and_then(|input| (day.time)(&input, &run_args.parts));
+            match result {
+                Ok(timings) => println!("{}", table_row(day.number, &name, &timings)),
+                Err(e) => {
+                    eprintln!(
+                        "Day {:02} ({name}) could not be benchmarked: {e}",
+                        day.number
+                    );
+                    failed = true;
+                }
+            }
+        }
+    }
+    !failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(input: &str) -> Vec<String> {
+        input.split(' ').map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let run_args = parse_args(&args("run 5 --part 2")).unwrap();
+        assert_eq!(
+            vec![5],
+            run_args.days.iter().map(|d| d.number).collect::<Vec<u32>>()
+        );
+        assert_eq!(vec![2], run_args.parts);
+
+        let run_args = parse_args(&args("run all")).unwrap();
+        assert_eq!(DAYS.len(), run_args.days.len());
+        assert_eq!(vec![1, 2], run_args.parts);
+
+        let run_args = parse_args(&args("verify all --part 1")).unwrap();
+        assert_eq!(Command::Verify, run_args.command);
+        assert_eq!(vec![1], run_args.parts);
+        assert_eq!(
+            Command::Record,
+            parse_args(&args("record 9")).unwrap().command
+        );
+    }
+
+    #[test]
+    fn test_parse_args_invalid() {
+        assert!(parse_args(&args("run 3")).is_err());
+        assert!(parse_args(&args("run 5 --part 3")).is_err());
+        assert!(parse_args(&args("walk 5")).is_err());
+        assert!(parse_args(&[]).is_err());
+    }
+}
@@ -1,6 +1,24 @@
-use day_01::{calibration_sum_2, read_lines};
+use std::process::ExitCode;
 
-fn main() {
-    let input: Vec<String> = read_lines("./input.txt");
-    println!("{}", calibration_sum_2(input));
+use aoc_common::{open_from_args, Solution};
+use day_01::Day01;
+
+fn main() -> ExitCode {
+    let input = match open_from_args(env!("CARGO_MANIFEST_DIR")) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match Day01::parse(input).and_then(|calibration| Day01::part2(&calibration)) {
+        Ok(sum) => {
+            println!("{sum}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -1,42 +1,94 @@
-use std::{collections::HashMap, fs::read_to_string};
-
-pub fn read_lines(filename: &str) -> Vec<String> {
-    read_to_string(filename)
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
+use std::{collections::HashMap, fmt, io::BufRead};
+
+use aoc_common::{read_lines, InputError, Solution, Span};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u32 = 1;
+
+    type Input = Vec<String>;
+    type Answer = u32;
+    type Error = Error;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, Self::Error> {
+        Ok(read_lines(reader)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        calibration_sum(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        calibration_sum_2(input)
+    }
 }
 
-pub fn parse_calibration_value(line: String) -> u32 {
-    let filtered: Vec<u32> = line
-        .chars()
-        .filter(|c| c.is_numeric())
-        .map(|c| c.to_digit(10).unwrap())
-        .collect();
-    let first_digit = filtered.first().unwrap();
-    let second_digit = filtered.last().unwrap();
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    Input(InputError),
+    // the line has no digits (or spelled out digits for part 2) to make a calibration value from
+    NoDigits(Span),
+}
 
-    (first_digit * 10) + second_digit
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(e) => write!(f, "{e}"),
+            Self::NoDigits(span) => write!(f, "no digits found in {span}"),
+        }
+    }
 }
 
-pub fn calibration_sum(input: Vec<String>) -> u32 {
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Input(e) => Some(e),
+            Self::NoDigits(_) => None,
+        }
+    }
+}
+
+impl Error {
+    fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::NoDigits(span) => Self::NoDigits(span.on_line(line)),
+            e => e,
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Self::Input(e)
+    }
+}
+
+pub fn parse_calibration_value(line: &str) -> Result<u32, Error> {
+    let filtered: Vec<u32> = line.chars().filter_map(|c| c.to_digit(10)).collect();
+    match (filtered.first(), filtered.last()) {
+        (Some(first_digit), Some(second_digit)) => Ok((first_digit * 10) + second_digit),
+        _ => Err(Error::NoDigits(Span::of(line, line))),
+    }
+}
+
+pub fn calibration_sum(input: &[String]) -> Result<u32, Error> {
     let mut sum: u32 = 0;
-    for line in input {
-        sum += parse_calibration_value(line)
+    for (i, line) in input.iter().enumerate() {
+        sum += parse_calibration_value(line).map_err(|e| e.on_line(i + 1))?;
     }
-    sum
+    Ok(sum)
 }
 
-pub fn calibration_sum_2(input: Vec<String>) -> u32 {
+pub fn calibration_sum_2(input: &[String]) -> Result<u32, Error> {
     let mut sum: u32 = 0;
-    for line in input {
-        sum += parse_calibration_value_2(line)
+    for (i, line) in input.iter().enumerate() {
+        sum += parse_calibration_value_2(line).map_err(|e| e.on_line(i + 1))?;
     }
-    sum
+    Ok(sum)
 }
 
-pub fn parse_calibration_value_2(line: String) -> u32 {
+pub fn parse_calibration_value_2(line: &str) -> Result<u32, Error> {
     let numbers: HashMap<&str, u32> = HashMap::from([
         ("one", 1),
         ("two", 2),
@@ -64,9 +116,12 @@ pub fn parse_calibration_value_2(line: String) -> u32 {
             values.insert(i, v);
         }
     }
-    let first = values.keys().min().unwrap();
-    let last = values.keys().max().unwrap();
-    (values.get(first).unwrap() * 10) + values.get(last).unwrap()
+    let first = values.keys().min().and_then(|i| values.get(i));
+    let last = values.keys().max().and_then(|i| values.get(i));
+    match (first, last) {
+        (Some(first), Some(last)) => Ok((first * 10) + last),
+        _ => Err(Error::NoDigits(Span::of(line, line))),
+    }
 }
 
 #[cfg(test)]
@@ -81,9 +136,9 @@ mod tests {
             String::from("a1b2c3d4e5f"), // 15
             String::from("treb7uchet"),  // 77
         ];
-        assert_eq!(parse_calibration_value(input[1].clone()), 38);
-        assert_eq!(parse_calibration_value(input[3].clone()), 77);
-        assert_eq!(calibration_sum(input), 142);
+        assert_eq!(parse_calibration_value(&input[1]), Ok(38));
+        assert_eq!(parse_calibration_value(&input[3]), Ok(77));
+        assert_eq!(calibration_sum(&input), Ok(142));
     }
 
     #[test]
@@ -98,10 +153,24 @@ mod tests {
             String::from("7pqrstsixteen"),         // 76
             String::from("8twosvdmcntf1hfive393"), // 83
         ];
-        assert_eq!(parse_calibration_value_2(input[1].clone()), 83);
-        assert_eq!(parse_calibration_value_2(input[5].clone()), 14);
-        assert_eq!(parse_calibration_value_2(input[6].clone()), 76);
-        assert_eq!(parse_calibration_value_2(input[7].clone()), 83);
-        assert_eq!(calibration_sum_2(input), 364);
+        assert_eq!(parse_calibration_value_2(&input[1]), Ok(83));
+        assert_eq!(parse_calibration_value_2(&input[5]), Ok(14));
+        assert_eq!(parse_calibration_value_2(&input[6]), Ok(76));
+        assert_eq!(parse_calibration_value_2(&input[7]), Ok(83));
+        assert_eq!(calibration_sum_2(&input), Ok(364));
+    }
+
+    #[test]
+    fn test_no_digits() {
+        let input = vec![String::from("1abc2"), String::from("pqrstuvwx")];
+        assert_eq!(
+            Err(Error::NoDigits(Span::new(2, 1, "pqrstuvwx"))),
+            calibration_sum(&input)
+        );
+        let input = vec![String::from("xtwone3four"), String::from("zero")];
+        assert_eq!(
+            Err(Error::NoDigits(Span::new(2, 1, "zero"))),
+            calibration_sum_2(&input)
+        );
     }
 }
@@ -1,10 +1,24 @@
-use day_02::{sum_valid_games, Colour};
-use std::collections::HashMap;
+use std::process::ExitCode;
 
-fn main() {
-    let valid: HashMap<Colour, u32> =
-        HashMap::from([(Colour::Red, 12), (Colour::Blue, 14), (Colour::Green, 13)]);
-    let sum = sum_valid_games("input.txt", valid);
+use aoc_common::{open_from_args, Solution};
+use day_02::Day02;
 
-    println!("Sum of ids for valid games is: {sum}");
+fn main() -> ExitCode {
+    let input = match open_from_args(env!("CARGO_MANIFEST_DIR")) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match Day02::parse(input).and_then(|games| Day02::part1(&games)) {
+        Ok(sum) => {
+            println!("Sum of ids for valid games is: {sum}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -1,6 +1,24 @@
-use day_02::sum_game_powers;
+use std::process::ExitCode;
 
-fn main() {
-    let sum = sum_game_powers("input.txt");
-    println!("Sum of powers for all games is: {sum}");
+use aoc_common::{open_from_args, Solution};
+use day_02::Day02;
+
+fn main() -> ExitCode {
+    let input = match open_from_args(env!("CARGO_MANIFEST_DIR")) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match Day02::parse(input).and_then(|games| Day02::part2(&games)) {
+        Ok(sum) => {
+            println!("Sum of powers for all games is: {sum}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
 }
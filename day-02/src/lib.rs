@@ -1,18 +1,98 @@
 use std::{
-    collections::HashMap,
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    io::BufRead,
 };
 
-#[derive(Debug, PartialEq, PartialOrd, Eq, Hash, Clone, Copy)]
+use aoc_common::{read_lines, InputError, Solution, Span};
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u32 = 2;
+
+    type Input = Vec<Game>;
+    type Answer = u32;
+    type Error = Error;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, Self::Error> {
+        parse_games(reader)
+    }
+
+    // sum of ids for games that are possible with only 12 red, 13 green and 14 blue cubes
+    fn part1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        let valid: HashMap<Colour, u32> =
+            HashMap::from([(Colour::Red, 12), (Colour::Blue, 14), (Colour::Green, 13)]);
+        Ok(sum_valid_games(input, &valid))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        Ok(sum_game_powers(input))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    Input(InputError),
+    // line doesn't start with "Game "
+    MissingGame(Span),
+    // no ": " between the game id and its rounds
+    MissingRounds(Span),
+    InvalidNumber(Span),
+    InvalidColour(Span),
+    // a cube should be a quantity followed by a colour e.g. "3 blue"
+    InvalidCube(Span),
+}
+
+impl Error {
+    fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::Input(e) => Self::Input(e),
+            Self::MissingGame(span) => Self::MissingGame(span.on_line(line)),
+            Self::MissingRounds(span) => Self::MissingRounds(span.on_line(line)),
+            Self::InvalidNumber(span) => Self::InvalidNumber(span.on_line(line)),
+            Self::InvalidColour(span) => Self::InvalidColour(span.on_line(line)),
+            Self::InvalidCube(span) => Self::InvalidCube(span.on_line(line)),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(e) => write!(f, "{e}"),
+            Self::MissingGame(span) => write!(f, "expected \"Game <id>:\" at start of {span}"),
+            Self::MissingRounds(span) => write!(f, "no rounds found after game id {span}"),
+            Self::InvalidNumber(span) => write!(f, "invalid number {span}"),
+            Self::InvalidColour(span) => write!(f, "invalid colour {span}"),
+            Self::InvalidCube(span) => write!(f, "invalid cube {span}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Input(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Self::Input(e)
+    }
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Clone, Copy)]
 pub enum Colour {
     Red,
-    Blue,
     Green,
+    Blue,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Cube {
     pub colour: Colour,
     pub quantity: u32,
@@ -20,10 +100,7 @@ pub struct Cube {
 
 impl Cube {
     pub fn from(colour: Colour, quantity: u32) -> Self {
-        Cube {
-            colour: colour,
-            quantity: quantity,
-        }
+        Cube { colour, quantity }
     }
 
     pub fn is_valid(&self, valid_cubes: &HashMap<Colour, u32>) -> bool {
@@ -34,20 +111,26 @@ impl Cube {
     }
 }
 
+impl PartialOrd for Cube {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for Cube {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.quantity.cmp(&other.quantity)
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Round {
     pub cubes: Vec<Cube>,
 }
 
 impl Round {
     pub fn from(cubes: Vec<Cube>) -> Self {
-        Self { cubes: cubes }
+        Self { cubes }
     }
 
     pub fn new() -> Self {
@@ -59,7 +142,7 @@ impl Round {
     }
 
     pub fn is_valid(&self, valid_cubes: &HashMap<Colour, u32>) -> bool {
-        self.cubes.iter().all(|c| c.is_valid(&valid_cubes))
+        self.cubes.iter().all(|c| c.is_valid(valid_cubes))
     }
 
     pub fn calculate_power(&self) -> u32 {
@@ -71,7 +154,7 @@ impl Round {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Game {
     pub id: u32,
     pub rounds: Vec<Round>,
@@ -79,17 +162,11 @@ pub struct Game {
 
 impl Game {
     pub fn from(id: u32, rounds: Vec<Round>) -> Self {
-        Self {
-            id: id,
-            rounds: rounds,
-        }
+        Self { id, rounds }
     }
 
     pub fn new(id: u32) -> Self {
-        Self {
-            id: id,
-            rounds: vec![],
-        }
+        Self { id, rounds: vec![] }
     }
 
     pub fn add_round(&mut self, round: Round) {
@@ -101,7 +178,8 @@ impl Game {
     }
 
     pub fn get_min_cubes(&self) -> Round {
-        let mut max_seen: HashMap<Colour, Cube> = HashMap::new();
+        // ordered by colour so the resulting round is always the same
+        let mut max_seen: BTreeMap<Colour, Cube> = BTreeMap::new();
         for round in &self.rounds {
             for cube in &round.cubes {
                 if cube > max_seen.entry(cube.colour).or_insert(*cube) {
@@ -115,51 +193,55 @@ impl Game {
     }
 }
 
-pub fn sum_valid_games(filename: &str, valid: HashMap<Colour, u32>) -> u32 {
-    let mut sum = 0;
-    if let Ok(lines) = read_lines(filename) {
-        for line in lines {
-            if let Ok(l) = line {
-                if let Ok(game) = parse_game(&l) {
-                    if game.rounds.iter().all(|r| r.is_valid(&valid)) {
-                        sum += game.id;
-                    }
-                }
-            }
-        }
-    }
-    sum
+pub fn sum_valid_games(games: &[Game], valid: &HashMap<Colour, u32>) -> u32 {
+    games
+        .iter()
+        .filter(|g| g.rounds.iter().all(|r| r.is_valid(valid)))
+        .map(|g| g.id)
+        .sum()
 }
 
-pub fn sum_game_powers(filename: &str) -> u32 {
-    let mut sum = 0;
+pub fn sum_game_powers(games: &[Game]) -> u32 {
+    games
+        .iter()
+        .map(|g| g.get_min_cubes().calculate_power())
+        .sum()
+}
 
-    if let Ok(lines) = read_lines(filename) {
-        for line in lines {
-            if let Ok(l) = line {
-                if let Ok(game) = parse_game(&l) {
-                    sum += game.get_min_cubes().calculate_power();
-                }
-            }
+// blank lines are skipped, but any other line that isn't a valid game is an error
+pub fn parse_games<R: BufRead>(reader: R) -> Result<Vec<Game>, Error> {
+    let mut games: Vec<Game> = Vec::new();
+    for (i, line) in read_lines(reader)?.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
         }
+        games.push(parse_game(line).map_err(|e| e.on_line(i + 1))?);
     }
-    sum
+    Ok(games)
 }
 
-fn parse_game(line: &str) -> Result<Game, &'static str> {
-    let line = &line[5..];
-    let parts: Vec<&str> = line.split(": ").collect();
-    let id = u32::from_str_radix(parts[0], 10).expect("could not parse id");
-    let rounds: Vec<&str> = parts[1].split("; ").collect();
+pub fn parse_game(line: &str) -> Result<Game, Error> {
+    let game = line
+        .strip_prefix("Game ")
+        .ok_or_else(|| Error::MissingGame(Span::of(line, line)))?;
+    let (id, rounds) = game
+        .split_once(": ")
+        .ok_or_else(|| Error::MissingRounds(Span::of(line, game)))?;
+    let id = id
+        .parse::<u32>()
+        .map_err(|_| Error::InvalidNumber(Span::of(line, id)))?;
     let mut game = Game::new(id);
-    for r in rounds {
-        let cubes: Vec<&str> = r.split(", ").collect();
+    for r in rounds.split("; ") {
         let mut round = Round::new();
-        for c in cubes {
-            let cube: Vec<&str> = c.split(' ').collect();
-            let quantity =
-                u32::from_str_radix(cube[0], 10).expect("could not parse number of cubes");
-            let colour: Colour = parse_colour(cube[1])?;
+        for c in r.split(", ") {
+            let (quantity, colour) = c
+                .split_once(' ')
+                .ok_or_else(|| Error::InvalidCube(Span::of(line, c)))?;
+            let quantity = quantity
+                .parse::<u32>()
+                .map_err(|_| Error::InvalidNumber(Span::of(line, quantity)))?;
+            let colour =
+                parse_colour(colour).ok_or_else(|| Error::InvalidColour(Span::of(line, colour)))?;
             round.add_cube(Cube::from(colour, quantity));
         }
         game.add_round(round);
@@ -167,24 +249,15 @@ fn parse_game(line: &str) -> Result<Game, &'static str> {
     Ok(game)
 }
 
-fn parse_colour(colour: &str) -> Result<Colour, &'static str> {
+fn parse_colour(colour: &str) -> Option<Colour> {
     match colour {
-        "green" => Ok(Colour::Green),
-        "blue" => Ok(Colour::Blue),
-        "red" => Ok(Colour::Red),
-        _ => Err("Failed to parse colour of cube"),
+        "green" => Some(Colour::Green),
+        "blue" => Some(Colour::Blue),
+        "red" => Some(Colour::Red),
+        _ => None,
     }
 }
 
-// shamelessly stolen from Rust By Example
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,4 +299,40 @@ mod tests {
 
         assert_eq!(expected, input.get_min_cubes());
     }
+
+    #[test]
+    fn test_example() {
+        let valid: HashMap<Colour, u32> =
+            HashMap::from([(Colour::Red, 12), (Colour::Blue, 14), (Colour::Green, 13)]);
+        let games = parse_games(aoc_common::open("test.txt").unwrap()).unwrap();
+        assert_eq!(5, games.len());
+        assert_eq!(8, sum_valid_games(&games, &valid));
+        assert_eq!(2286, sum_game_powers(&games));
+    }
+
+    #[test]
+    fn test_parse_game_errors() {
+        assert_eq!(
+            Err(Error::InvalidColour(Span::new(1, 19, "purple"))),
+            parse_game("Game 1: 3 blue, 4 purple; 1 red")
+        );
+        assert_eq!(
+            Err(Error::InvalidNumber(Span::new(1, 6, "x"))),
+            parse_game("Game x: 3 blue")
+        );
+        assert_eq!(
+            Err(Error::InvalidCube(Span::new(1, 17, "4red"))),
+            parse_game("Game 1: 3 blue, 4red")
+        );
+        assert_eq!(
+            Err(Error::MissingGame(Span::new(1, 1, "Round 1: 3 blue"))),
+            parse_game("Round 1: 3 blue")
+        );
+
+        let input = "Game 1: 3 blue\n\nGame 2: 1 red, x green";
+        assert_eq!(
+            Err(Error::InvalidNumber(Span::new(3, 16, "x"))),
+            parse_games(input.as_bytes())
+        );
+    }
 }
@@ -1,12 +1,56 @@
-use day_05::{parse_almanac, Almanac};
+use std::{env, path::Path, process::ExitCode};
 
-fn main() {
-    let almanac: Almanac = parse_almanac("input.txt");
-    let mut locations: Vec<u64> = Vec::new();
+use aoc_common::{InputSource, Solution};
+use day_05::{Day05, Trace};
 
-    for seed in almanac.seeds.iter() {
-        locations.push(almanac.get_location_from_seed(*seed));
+fn main() -> ExitCode {
+    // --trace-seed <n> or --trace-location <n> print each step of a lookup instead of the answer
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let trace = match Trace::take_from_args(&mut args) {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let input = match InputSource::from_args(args, Path::new(env!("CARGO_MANIFEST_DIR")))
+        .and_then(|source| source.open())
+    {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let almanac = match Day05::parse(input) {
+        Ok(almanac) => almanac,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(trace) = trace {
+        return match trace.run(&almanac) {
+            Ok(steps) => {
+                for step in steps {
+                    println!("{step}");
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    match Day05::part1(&almanac) {
+        Ok(min_location) => {
+            println!("Lowest location for almanac's seeds: {min_location}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
     }
-    let min_location = locations.iter().min().unwrap();
-    println!("Lowest location for almanac's seeds: {min_location}");
 }
@@ -1,16 +1,27 @@
-//use std::collections::HashMap;
+use std::process::ExitCode;
 
-use day_05::{parse_almanac, Almanac};
+use aoc_common::{open_from_args, Solution};
+use day_05::{Almanac, Day05};
 
-fn main() {
-    let almanac: Almanac = parse_almanac("input.txt");
-    let mut location: u64 = 0;
-    let mut seed = almanac.get_seed_from_location(location);
-
-    // very slow
-    while !almanac.contains_seed(seed) {
-        location += 1;
-        seed = almanac.get_seed_from_location(location);
+fn main() -> ExitCode {
+    let almanac: Almanac = match open_from_args(env!("CARGO_MANIFEST_DIR"))
+        .map_err(day_05::Error::from)
+        .and_then(Day05::parse)
+    {
+        Ok(almanac) => almanac,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match almanac.lowest_seed_range_location() {
+        Ok((location, seed)) => {
+            println!("Lowest location for which almanac has a seed: {location} for seed {seed}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
     }
-    println!("Lowest location for which almanac has a seed: {location} for seed {seed}");
 }
@@ -1,11 +1,192 @@
 use std::{
     cmp::Ordering,
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
+    collections::{HashMap, VecDeque},
+    fmt,
+    io::BufRead,
 };
 
-#[derive(Debug, Eq, PartialEq, PartialOrd, Clone, Copy)]
+use aoc_common::{read_blocks, InputError, Interval, IntervalSet, Solution, Span};
+use serde::{Deserialize, Serialize};
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u32 = 5;
+
+    type Input = Almanac;
+    type Answer = u64;
+    type Error = Error;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, Self::Error> {
+        parse_almanac(reader)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        input.lowest_seed_location()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        input
+            .lowest_seed_range_location()
+            .map(|(location, _)| location)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    Input(InputError),
+    NoSeeds,
+    // none of the maps link the two categories
+    NoPath { from: String, to: String },
+    InvalidNumber(Span),
+    // a map range line that isn't exactly three numbers
+    InvalidMapRange(Span),
+    // a line that isn't the seeds, a map header or part of a map
+    UnexpectedLine(Span),
+    // the last of an odd number of seeds, which has no length to make a seed range with
+    UnpairedSeed(Span),
+    // the almanac parsed but failed validation in strict mode
+    Invalid(Vec<Issue>),
+    // serde_json's errors can't be compared, so this keeps its message
+    Json(String),
+}
+
+impl Error {
+    fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::InvalidNumber(span) => Self::InvalidNumber(span.on_line(line)),
+            Self::InvalidMapRange(span) => Self::InvalidMapRange(span.on_line(line)),
+            Self::UnexpectedLine(span) => Self::UnexpectedLine(span.on_line(line)),
+            Self::UnpairedSeed(span) => Self::UnpairedSeed(span.on_line(line)),
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(e) => write!(f, "{e}"),
+            Self::NoSeeds => write!(f, "almanac has no seeds"),
+            Self::NoPath { from, to } => write!(f, "no maps lead from {from} to {to}"),
+            Self::InvalidNumber(span) => write!(f, "invalid number {span}"),
+            Self::InvalidMapRange(span) => write!(
+                f,
+                "expected destination, source and length for map range {span}"
+            ),
+            Self::UnexpectedLine(span) => write!(f, "unexpected line {span}"),
+            Self::UnpairedSeed(span) => write!(f, "seed has no range length {span}"),
+            Self::Json(message) => write!(f, "invalid almanac json: {message}"),
+            Self::Invalid(issues) => {
+                write!(f, "invalid almanac:")?;
+                for issue in issues {
+                    write!(f, "\n  {issue}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Input(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Self::Input(e)
+    }
+}
+
+// something found when validating an almanac, where everything but a gap makes the answers
+// depend on the order of the ranges or on numbers that don't fit in a u64
+#[derive(Debug, PartialEq, Clone)]
+pub enum Issue {
+    // two ranges in the map cover some of the same sources
+    OverlappingSources {
+        map: String,
+        first: MapRange,
+        second: MapRange,
+    },
+    // two ranges send some numbers to the same place, so the map can't be reversed
+    OverlappingDestinations {
+        map: String,
+        first: MapRange,
+        second: MapRange,
+    },
+    // numbers that no range covers pass through unchanged, landing on the destination of a range
+    PassThroughCollision {
+        map: String,
+        range: MapRange,
+        numbers: SeedRange,
+    },
+    // the source or destination of a range goes past u64::MAX
+    MapRangeOverflow {
+        map: String,
+        range: MapRange,
+    },
+    SeedRangeOverflow(SeedRange),
+    // numbers between the ranges of a map that pass through unchanged, which is allowed
+    Gap {
+        map: String,
+        numbers: SeedRange,
+    },
+}
+
+impl Issue {
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::Gap { .. })
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OverlappingSources { map, first, second } => write!(
+                f,
+                "{map} map has ranges from {} and {} with overlapping sources",
+                first.source, second.source
+            ),
+            Self::OverlappingDestinations { map, first, second } => write!(
+                f,
+                "{map} map has ranges from {} and {} with overlapping destinations",
+                first.source, second.source
+            ),
+            Self::PassThroughCollision {
+                map,
+                range,
+                numbers,
+            } => write!(
+                f,
+                "{map} map passes {} through onto the destination of the range from {}",
+                numbers.interval(),
+                range.source
+            ),
+            Self::MapRangeOverflow { map, range } => write!(
+                f,
+                "{map} map has a range from {} of length {} that overflows",
+                range.source, range.range
+            ),
+            Self::SeedRangeOverflow(seeds) => write!(
+                f,
+                "seed range from {} of length {} overflows",
+                seeds.start, seeds.length
+            ),
+            Self::Gap { map, numbers } => write!(
+                f,
+                "{map} map passes {} through unchanged",
+                numbers.interval()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct MapRange {
     source: u64,
     dest: u64,
@@ -14,7 +195,7 @@ pub struct MapRange {
 
 impl MapRange {
     pub fn within(&self, n: u64) -> bool {
-        (self.source <= n) && ((self.source + self.range) > n)
+        self.source_interval().contains(n.into())
     }
 
     pub fn lookup(&self, n: u64) -> Option<u64> {
@@ -26,7 +207,7 @@ impl MapRange {
     }
 
     pub fn within_dest(&self, n: u64) -> bool {
-        (self.dest <= n) && ((self.dest + self.range) > n)
+        self.dest_interval().contains(n.into())
     }
 
     pub fn reverse_lookup(&self, n: u64) -> Option<u64> {
@@ -39,11 +220,49 @@ impl MapRange {
 
     pub fn new(source: u64, dest: u64, range: u64) -> Self {
         Self {
-            source: source,
-            dest: dest,
-            range: range,
+            source,
+            dest,
+            range,
         }
     }
+
+    pub fn source(&self) -> u64 {
+        self.source
+    }
+
+    pub fn dest(&self) -> u64 {
+        self.dest
+    }
+
+    pub fn range(&self) -> u64 {
+        self.range
+    }
+
+    pub fn source_interval(&self) -> Interval {
+        Interval::with_len(self.source.into(), self.range.into())
+    }
+
+    pub fn dest_interval(&self) -> Interval {
+        Interval::with_len(self.dest.into(), self.range.into())
+    }
+
+    // how far numbers in the range move when they are looked up
+    pub fn offset(&self) -> i128 {
+        self.dest as i128 - self.source as i128
+    }
+}
+
+// the same order as the input, destination first
+impl fmt::Display for MapRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.dest, self.source, self.range)
+    }
+}
+
+impl PartialOrd for MapRange {
+    fn partial_cmp(&self, other: &MapRange) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Ord for MapRange {
@@ -52,35 +271,390 @@ impl Ord for MapRange {
     }
 }
 
-#[derive(Debug, PartialEq)]
+// a map from one category to another, e.g. seed-to-soil. an empty map sends every number to
+// itself
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+#[serde(from = "AlmanacMapJson", into = "AlmanacMapJson")]
 pub struct AlmanacMap {
+    from: String,
+    to: String,
+    // sorted by source
     ranges: Vec<MapRange>,
+    // indexes into ranges sorted by destination, for reverse lookups
+    by_dest: Vec<usize>,
 }
 
 impl AlmanacMap {
+    // a map without category names, for when only the ranges matter
+    pub fn new(ranges: Vec<MapRange>) -> Self {
+        Self::named("", "", ranges)
+    }
+
+    pub fn named(from: &str, to: &str, mut ranges: Vec<MapRange>) -> Self {
+        ranges.sort();
+        let mut by_dest: Vec<usize> = (0..ranges.len()).collect();
+        by_dest.sort_by_key(|i| ranges[*i].dest);
+        Self {
+            from: String::from(from),
+            to: String::from(to),
+            ranges,
+            by_dest,
+        }
+    }
+
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    pub fn ranges(&self) -> &[MapRange] {
+        &self.ranges
+    }
+
+    // the range the input falls in, if any
+    pub fn find(&self, input: u64) -> Option<&MapRange> {
+        let i = self.ranges.partition_point(|r| r.source <= input);
+        i.checked_sub(1)
+            .map(|i| &self.ranges[i])
+            .filter(|r| r.within(input))
+    }
+
+    // the range whose destination the input falls in, if any
+    pub fn find_reverse(&self, input: u64) -> Option<&MapRange> {
+        let i = self
+            .by_dest
+            .partition_point(|i| self.ranges[*i].dest <= input);
+        i.checked_sub(1)
+            .map(|i| &self.ranges[self.by_dest[i]])
+            .filter(|r| r.within_dest(input))
+    }
+
     pub fn lookup(&self, input: u64) -> u64 {
+        self.find(input)
+            .and_then(|r| r.lookup(input))
+            // if none was found, the destination is the same as the source
+            .unwrap_or(input)
+    }
+
+    pub fn reverse_lookup(&self, input: u64) -> u64 {
+        self.find_reverse(input)
+            .and_then(|r| r.reverse_lookup(input))
+            .unwrap_or(input)
+    }
+
+    // looks up inputs that are already sorted in a single pass alongside the ranges, instead of
+    // searching for each one. the results are in the same order as the inputs
+    pub fn lookup_sorted(&self, inputs: &[u64]) -> Vec<u64> {
+        debug_assert!(inputs.is_sorted(), "inputs should be sorted");
+        let mut ranges = self.ranges.iter().peekable();
+        inputs
+            .iter()
+            .map(|n| {
+                // skip ranges that end before this input, which also end before any later ones
+                while ranges
+                    .next_if(|r| r.source_interval().end() <= (*n).into())
+                    .is_some()
+                {}
+                ranges.peek().and_then(|r| r.lookup(*n)).unwrap_or(*n)
+            })
+            .collect()
+    }
+
+    // maps a whole range of inputs at once, splitting it wherever it crosses the edge of a map
+    // range. the parts that aren't covered by any map range pass through unchanged
+    pub fn map_range(&self, input: SeedRange) -> Vec<SeedRange> {
+        self.map_interval(input.interval())
+            .into_iter()
+            .map(SeedRange::from)
+            .collect()
+    }
+
+    // map_range for an interval, which leaves out empty pieces
+    fn map_interval(&self, input: Interval) -> Vec<Interval> {
+        let mut mapped: Vec<Interval> = Vec::new();
+        // ranges are sorted by source, so walk through them from the start of the input
+        let mut rest = input;
         for range in &self.ranges {
-            match range.lookup(input) {
-                Some(r) => return r,
-                None => (),
+            let overlap = rest.intersection(&range.source_interval());
+            if overlap.is_empty() {
+                continue;
             }
+            mapped.push(Interval::new(rest.start(), overlap.start()));
+            mapped.push(overlap.shift(range.offset()));
+            rest = Interval::new(overlap.end(), rest.end());
         }
-        // if none was found, the destination is the same as the source
-        input
+        mapped.push(rest);
+        mapped.retain(|i| !i.is_empty());
+        mapped
     }
 
-    pub fn reverse_lookup(&self, input: u64) -> u64 {
+    // the name from the header, e.g. seed-to-soil
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.from, self.to)
+    }
+
+    // every number a range starts from
+    pub fn sources(&self) -> IntervalSet {
+        self.ranges.iter().map(MapRange::source_interval).collect()
+    }
+
+    // every number no range covers, which pass through unchanged
+    pub fn pass_through(&self) -> Vec<SeedRange> {
+        self.sources()
+            .complement(every_number())
+            .iter()
+            .map(|i| SeedRange::from(*i))
+            .collect()
+    }
+
+    // looks up a whole set of numbers at once. unlike map_range the result is a set, so it comes
+    // out sorted with any pieces that land next to each other joined up
+    pub fn map_set(&self, inputs: &IntervalSet) -> IntervalSet {
+        let mut mapped = inputs.difference(&self.sources());
+        for range in &self.ranges {
+            let moved = inputs
+                .intersection(&IntervalSet::from(range.source_interval()))
+                .shift(range.offset());
+            mapped = mapped.union(&moved);
+        }
+        mapped
+    }
+
+    // checks the ranges don't overlap or overflow, and that nothing passes through onto the
+    // destination of a range, along with any gaps between the ranges
+    pub fn validate(&self) -> Vec<Issue> {
+        let map = self.name();
+        let mut issues: Vec<Issue> = Vec::new();
         for range in &self.ranges {
-            match range.reverse_lookup(input) {
-                Some(r) => return r,
-                None => (),
+            if range.source_interval().end() > every_number().end()
+                || range.dest_interval().end() > every_number().end()
+            {
+                issues.push(Issue::MapRangeOverflow {
+                    map: map.clone(),
+                    range: *range,
+                });
             }
         }
-        input
+
+        // ranges are sorted by source, so each one only needs to be checked against the one
+        // before it that reaches the furthest
+        let overlaps = |ranges: &[MapRange], interval: fn(&MapRange) -> Interval| {
+            let mut found: Vec<(MapRange, MapRange)> = Vec::new();
+            let mut furthest: Option<MapRange> = None;
+            for range in ranges {
+                match furthest {
+                    Some(f) if !interval(&f).intersection(&interval(range)).is_empty() => {
+                        found.push((f, *range))
+                    }
+                    _ => (),
+                }
+                if furthest.is_none_or(|f| interval(range).end() > interval(&f).end()) {
+                    furthest = Some(*range);
+                }
+            }
+            found
+        };
+        for (first, second) in overlaps(&self.ranges, MapRange::source_interval) {
+            issues.push(Issue::OverlappingSources {
+                map: map.clone(),
+                first,
+                second,
+            });
+        }
+        let mut by_dest = self.ranges.clone();
+        by_dest.sort_by_key(|r| r.dest);
+        for (first, second) in overlaps(&by_dest, MapRange::dest_interval) {
+            issues.push(Issue::OverlappingDestinations {
+                map: map.clone(),
+                first,
+                second,
+            });
+        }
+
+        let pass_through = self.sources().complement(every_number());
+        for range in &self.ranges {
+            let collisions = pass_through.intersection(&IntervalSet::from(range.dest_interval()));
+            for numbers in &collisions {
+                issues.push(Issue::PassThroughCollision {
+                    map: map.clone(),
+                    range: *range,
+                    numbers: SeedRange::from(*numbers),
+                });
+            }
+        }
+
+        // everything before the first range and after the last one passes through, so only the
+        // numbers in between count as gaps
+        let sources = self.sources();
+        let bounds = match (sources.intervals().first(), sources.intervals().last()) {
+            (Some(first), Some(last)) => Interval::new(first.start(), last.end()),
+            _ => Interval::new(0, 0),
+        };
+        for numbers in &sources.complement(bounds) {
+            issues.push(Issue::Gap {
+                map: map.clone(),
+                numbers: SeedRange::from(*numbers),
+            });
+        }
+        issues
+    }
+
+    pub fn map_ranges(&self, inputs: &[SeedRange]) -> Vec<SeedRange> {
+        inputs.iter().flat_map(|r| self.map_range(*r)).collect()
+    }
+
+    // a single map that does the same as looking up in this map and then in next, going from
+    // this map's source category to next's destination. an unnamed map takes the names of the
+    // map it is composed with
+    pub fn compose(&self, next: &AlmanacMap) -> AlmanacMap {
+        let mut ranges: Vec<MapRange> = Vec::new();
+        // split up everything this map covers, including the gaps between its ranges which pass
+        // through unchanged, by where it lands in the next map
+        let mut start = 0;
+        for range in &self.ranges {
+            let source = range.source_interval();
+            let gap = Interval::new(start, source.start());
+            push_pieces(&mut ranges, gap, next.map_interval(gap));
+            push_pieces(
+                &mut ranges,
+                source,
+                next.map_interval(range.dest_interval()),
+            );
+            start = start.max(source.end());
+        }
+        // past the end of this map only the next map does anything
+        let next_end = next.sources().intervals().last().map_or(0, Interval::end);
+        let rest = Interval::new(start, next_end);
+        push_pieces(&mut ranges, rest, next.map_interval(rest));
+        let name = |name: &String, other: &String| match name.as_str() {
+            "" => other.clone(),
+            _ => name.clone(),
+        };
+        AlmanacMap::named(
+            &name(&self.from, &next.from),
+            &name(&next.to, &self.to),
+            ranges,
+        )
+    }
+
+    // swaps the source and destination of every range. this is only the exact inverse if no two
+    // numbers map to the same place, which holds for puzzle inputs
+    pub fn inverse(&self) -> AlmanacMap {
+        AlmanacMap::named(
+            &self.to,
+            &self.from,
+            self.ranges
+                .iter()
+                .map(|r| MapRange::new(r.dest, r.source, r.range))
+                .collect(),
+        )
     }
 }
 
-#[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Copy)]
+// adds the pieces the numbers in source were split into by map_interval, which come out in the
+// same order as the input, leaving out pieces that don't move and joining up pieces that move by
+// the same amount as the range before them
+fn push_pieces(ranges: &mut Vec<MapRange>, source: Interval, pieces: Vec<Interval>) {
+    let mut start = source.start();
+    for piece in pieces {
+        let from = Interval::with_len(start, piece.len());
+        if piece.start() != from.start() {
+            match ranges.last_mut() {
+                Some(last)
+                    if last.source_interval().end() == from.start()
+                        && last.dest_interval().end() == piece.start() =>
+                {
+                    last.range += to_u64(piece.len())
+                }
+                _ => ranges.push(MapRange::new(
+                    to_u64(from.start()),
+                    to_u64(piece.start()),
+                    to_u64(piece.len()),
+                )),
+            }
+        }
+        start = from.end();
+    }
+}
+
+// every u64, which is where numbers that no range covers pass through
+fn every_number() -> Interval {
+    Interval::new(0, u128::from(u64::MAX) + 1)
+}
+
+// interval bounds only go past u64::MAX for ranges that overflow, so they stop there
+fn to_u64(n: u128) -> u64 {
+    u64::try_from(n).unwrap_or(u64::MAX)
+}
+
+// the header and a line for each range, like in the input
+impl fmt::Display for AlmanacMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} map:", self.name())?;
+        for range in &self.ranges {
+            write!(f, "\n{range}")?;
+        }
+        Ok(())
+    }
+}
+
+// the destination index is rebuilt from the ranges rather than being saved
+#[derive(Serialize, Deserialize)]
+struct AlmanacMapJson {
+    from: String,
+    to: String,
+    ranges: Vec<MapRange>,
+}
+
+impl From<AlmanacMapJson> for AlmanacMap {
+    fn from(json: AlmanacMapJson) -> Self {
+        AlmanacMap::named(&json.from, &json.to, json.ranges)
+    }
+}
+
+impl From<AlmanacMap> for AlmanacMapJson {
+    fn from(map: AlmanacMap) -> Self {
+        AlmanacMapJson {
+            from: map.from,
+            to: map.to,
+            ranges: map.ranges,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct AlmanacJson {
+    seeds: Vec<u64>,
+    maps: Vec<AlmanacMap>,
+}
+
+impl TryFrom<AlmanacJson> for Almanac {
+    type Error = String;
+
+    fn try_from(json: AlmanacJson) -> Result<Self, Self::Error> {
+        let seed_ranges = build_seed_ranges(&json.seeds)
+            .ok_or_else(|| format!("the last of {} seeds has no range length", json.seeds.len()))?;
+        Ok(Almanac {
+            seed_ranges,
+            seeds: json.seeds,
+            maps: json.maps,
+        })
+    }
+}
+
+impl From<Almanac> for AlmanacJson {
+    fn from(almanac: Almanac) -> Self {
+        AlmanacJson {
+            seeds: almanac.seeds,
+            maps: almanac.maps,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub struct SeedRange {
     start: u64,
     length: u64,
@@ -88,14 +662,37 @@ pub struct SeedRange {
 
 impl SeedRange {
     pub fn within(&self, n: u64) -> bool {
-        (n >= self.start) && (n < self.start + self.length)
+        self.interval().contains(n.into())
     }
 
     pub fn new(start: u64, length: u64) -> Self {
-        Self {
-            start: start,
-            length: length,
-        }
+        Self { start, length }
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn interval(&self) -> Interval {
+        Interval::with_len(self.start.into(), self.length.into())
+    }
+}
+
+impl From<Interval> for SeedRange {
+    fn from(interval: Interval) -> Self {
+        Self::new(to_u64(interval.start()), to_u64(interval.len()))
+    }
+}
+
+impl fmt::Display for SeedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.start, self.length)
+    }
+}
+
+impl PartialOrd for SeedRange {
+    fn partial_cmp(&self, other: &SeedRange) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -105,7 +702,10 @@ impl Ord for SeedRange {
     }
 }
 
-#[derive(Debug, PartialEq)]
+// the seed ranges are only written out as the seeds they come from, so they can't disagree when
+// the JSON is read back in
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+#[serde(try_from = "AlmanacJson", into = "AlmanacJson")]
 pub struct Almanac {
     pub seeds: Vec<u64>,
     pub seed_ranges: Vec<SeedRange>,
@@ -126,87 +726,417 @@ impl Almanac {
         }
     }
 
-    pub fn get_location_from_seed(&self, seed: u64) -> u64 {
-        let mut result: u64 = seed;
-        for m in self.maps.iter() {
-            result = m.lookup(result);
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::Json(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| Error::Json(e.to_string()))
+    }
+
+    // every issue with the seed ranges and the maps, including gaps which aren't errors
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues: Vec<Issue> = self
+            .seed_ranges
+            .iter()
+            .filter(|r| r.interval().end() > every_number().end())
+            .map(|r| Issue::SeedRangeOverflow(*r))
+            .collect();
+        for m in &self.maps {
+            issues.extend(m.validate());
+        }
+        issues
+    }
+
+    // every category named by the maps, in the order they first appear
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = Vec::new();
+        for m in &self.maps {
+            for category in [m.from.as_str(), m.to.as_str()] {
+                if !categories.contains(&category) {
+                    categories.push(category);
+                }
+            }
         }
-        result
+        categories
+    }
+
+    // finds the shortest chain of maps leading from one category to another with a breadth
+    // first search, where a map can be used backwards to go from its destination to its source
+    fn path<'a>(&'a self, from: &'a str, to: &str) -> Result<Vec<Step<'a>>, Error> {
+        // how each category was first reached, which is none for the starting category
+        let mut reached: HashMap<&str, Option<Step>> = HashMap::from([(from, None)]);
+        let mut queue: VecDeque<&str> = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut steps: Vec<Step> = Vec::new();
+                let mut current = category;
+                while let Some(Some(step)) = reached.get(current) {
+                    steps.push(*step);
+                    current = step.from();
+                }
+                steps.reverse();
+                return Ok(steps);
+            }
+            for m in &self.maps {
+                let step = if m.from == category {
+                    Step::Forward(m)
+                } else if m.to == category {
+                    Step::Reverse(m)
+                } else {
+                    continue;
+                };
+                if !reached.contains_key(step.to()) {
+                    reached.insert(step.to(), Some(step));
+                    queue.push_back(step.to());
+                }
+            }
+        }
+        Err(Error::NoPath {
+            from: String::from(from),
+            to: String::from(to),
+        })
+    }
+
+    // converts a number from one category to another, e.g. convert("soil", "humidity", 81),
+    // going through as many maps as it takes
+    pub fn convert(&self, from: &str, to: &str, value: u64) -> Result<u64, Error> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(value, |value, step| step.lookup(value)))
+    }
+
+    pub fn convert_ranges(
+        &self,
+        from: &str,
+        to: &str,
+        ranges: &[SeedRange],
+    ) -> Result<Vec<SeedRange>, Error> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(ranges.to_vec(), |ranges, step| {
+                step.map().map_ranges(&ranges)
+            }))
+    }
+
+    // the maps between two categories folded into one
+    pub fn composed(&self, from: &str, to: &str) -> Result<AlmanacMap, Error> {
+        let start = AlmanacMap::named(from, from, Vec::new());
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(start, |composed, step| composed.compose(&step.map())))
+    }
+
+    // the same as convert, but keeps every step along the way
+    pub fn trace(&self, from: &str, to: &str, value: u64) -> Result<Vec<TraceStep>, Error> {
+        let mut value = value;
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .map(|step| {
+                let traced = step.trace(value);
+                value = traced.output;
+                traced
+            })
+            .collect())
+    }
+
+    pub fn get_location_from_seed(&self, seed: u64) -> Result<u64, Error> {
+        self.convert("seed", "location", seed)
     }
 
     pub fn contains_seed(&self, seed: u64) -> bool {
         self.seed_ranges.iter().any(|s| s.within(seed))
     }
 
-    pub fn get_seed_from_location(&self, location: u64) -> u64 {
-        let mut result: u64 = location;
-        // iterate from last map back to first
-        for m in self.maps.iter().rev() {
-            result = m.reverse_lookup(result);
+    // every map folded into one that goes straight from seed to location
+    pub fn seed_to_location(&self) -> Result<AlmanacMap, Error> {
+        self.composed("seed", "location")
+    }
+
+    pub fn location_to_seed(&self) -> Result<AlmanacMap, Error> {
+        self.composed("location", "seed")
+    }
+
+    pub fn get_seed_from_location(&self, location: u64) -> Result<u64, Error> {
+        self.convert("location", "seed", location)
+    }
+
+    // for part 1
+    pub fn lowest_seed_location(&self) -> Result<u64, Error> {
+        let path = self.path("seed", "location")?;
+        self.seeds
+            .iter()
+            .map(|s| path.iter().fold(*s, |value, step| step.lookup(value)))
+            .min()
+            .ok_or(Error::NoSeeds)
+    }
+
+    // pushes every seed range through all of the maps, giving the ranges of locations the seeds
+    // end up at
+    pub fn location_ranges(&self) -> Result<Vec<SeedRange>, Error> {
+        self.convert_ranges("seed", "location", &self.seed_ranges)
+    }
+
+    // like convert_ranges, but for a set of numbers
+    pub fn convert_set(
+        &self,
+        from: &str,
+        to: &str,
+        numbers: &IntervalSet,
+    ) -> Result<IntervalSet, Error> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(numbers.clone(), |numbers, step| {
+                step.map().map_set(&numbers)
+            }))
+    }
+
+    // for part 2, returns the lowest location along with the seed it came from
+    pub fn lowest_seed_range_location(&self) -> Result<(u64, u64), Error> {
+        let seeds: IntervalSet = self.seed_ranges.iter().map(SeedRange::interval).collect();
+        let location = self
+            .convert_set("seed", "location", &seeds)?
+            .min()
+            .map(to_u64)
+            .ok_or(Error::NoSeeds)?;
+        Ok((location, self.get_seed_from_location(location)?))
+    }
+}
+
+// writes the almanac back out in the same format as the input, so parsing it again gives the
+// same almanac. the ranges in each map come out sorted by source
+impl fmt::Display for Almanac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seeds:")?;
+        for seed in &self.seeds {
+            write!(f, " {seed}")?;
+        }
+        for m in &self.maps {
+            write!(f, "\n\n{m}")?;
+        }
+        writeln!(f)
+    }
+}
+
+// one map along a path between categories, which is either followed from source to destination
+// or backwards
+#[derive(Debug, Clone, Copy)]
+enum Step<'a> {
+    Forward(&'a AlmanacMap),
+    Reverse(&'a AlmanacMap),
+}
+
+impl<'a> Step<'a> {
+    fn from(&self) -> &'a str {
+        match self {
+            Self::Forward(m) => &m.from,
+            Self::Reverse(m) => &m.to,
+        }
+    }
+
+    fn to(&self) -> &'a str {
+        match self {
+            Self::Forward(m) => &m.to,
+            Self::Reverse(m) => &m.from,
+        }
+    }
+
+    fn lookup(&self, n: u64) -> u64 {
+        match self {
+            Self::Forward(m) => m.lookup(n),
+            Self::Reverse(m) => m.reverse_lookup(n),
+        }
+    }
+
+    fn map(&self) -> AlmanacMap {
+        match self {
+            Self::Forward(m) => (*m).clone(),
+            Self::Reverse(m) => m.inverse(),
+        }
+    }
+
+    fn trace(&self, n: u64) -> TraceStep {
+        let (range, reversed) = match self {
+            Self::Forward(m) => (m.find(n), false),
+            Self::Reverse(m) => (m.find_reverse(n), true),
+        };
+        TraceStep {
+            from: String::from(self.from()),
+            to: String::from(self.to()),
+            input: n,
+            output: self.lookup(n),
+            range: range.copied(),
+            reversed,
+        }
+    }
+}
+
+// one map gone through by a trace
+#[derive(Debug, PartialEq, Clone)]
+pub struct TraceStep {
+    pub from: String,
+    pub to: String,
+    pub input: u64,
+    pub output: u64,
+    // the range that matched, or none if the number passed through unchanged
+    pub range: Option<MapRange>,
+    // whether the map was used backwards, from its destination to its source
+    pub reversed: bool,
+}
+
+// e.g. "seed 79 -> soil 81 via 52 50 48", with the range written as it is in the input
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} -> {} {} via ",
+            self.from, self.input, self.to, self.output
+        )?;
+        match self.range {
+            Some(range) if self.reversed => write!(f, "{range} reversed"),
+            Some(range) => write!(f, "{range}"),
+            None => write!(f, "identity"),
         }
-        result
     }
 }
 
-// this function sucks
-pub fn parse_almanac(filename: &str) -> Almanac {
+// what the part binaries' trace flags ask for
+#[derive(Debug, PartialEq)]
+pub enum Trace {
+    Seed(u64),
+    Location(u64),
+}
+
+impl Trace {
+    // pulls --trace-seed <n> or --trace-location <n> out of the arguments, leaving the rest to
+    // be used for the input
+    pub fn take_from_args(args: &mut Vec<String>) -> Result<Option<Trace>, InputError> {
+        let Some(i) = args
+            .iter()
+            .position(|a| a == "--trace-seed" || a == "--trace-location")
+        else {
+            return Ok(None);
+        };
+        let flag = args.remove(i);
+        if i >= args.len() {
+            return Err(InputError::InvalidArgs(format!(
+                "No number given after {flag}"
+            )));
+        }
+        let value = args.remove(i);
+        let n: u64 = value
+            .parse()
+            .map_err(|_| InputError::InvalidArgs(format!("Invalid number for {flag}: {value}")))?;
+        Ok(Some(match flag.as_str() {
+            "--trace-seed" => Trace::Seed(n),
+            _ => Trace::Location(n),
+        }))
+    }
+
+    pub fn run(&self, almanac: &Almanac) -> Result<Vec<TraceStep>, Error> {
+        match self {
+            Self::Seed(seed) => almanac.trace("seed", "location", *seed),
+            Self::Location(location) => almanac.trace("location", "seed", *location),
+        }
+    }
+}
+
+// each block of the input is either the seeds line or a map with a header line
+pub fn parse_almanac<R: BufRead>(reader: R) -> Result<Almanac, Error> {
     let mut almanac = Almanac::new();
-    let mut in_map = false;
-    let mut current_map: Vec<MapRange> = Vec::new();
-    if let Ok(lines) = read_lines(filename) {
-        for line in lines {
-            if let Ok(l) = line {
-                if l.starts_with("seeds: ") {
-                    let seeds = parse_seeds(&l);
-                    almanac.seed_ranges = build_seed_ranges(&seeds);
-                    // for part 1
-                    almanac.seeds = seeds;
-                } else if l.contains("map") {
-                    // will start to process map on next iteration
-                    in_map = true;
-                // a blank line indicates end of the map
-                } else if l.is_empty() {
-                    in_map = false;
-                    if current_map.len() > 0 {
-                        current_map.sort();
-                        almanac.insert_map(AlmanacMap {
-                            ranges: current_map.clone(),
-                        });
-                        current_map.clear();
-                    }
-                } else if in_map {
-                    current_map.push(parse_map_range(&l));
-                }
+    for block in read_blocks(reader)? {
+        let mut lines = block.numbered();
+        // blocks are never empty, so there is always a first line
+        let Some((header_line, header)) = lines.next() else {
+            continue;
+        };
+        if header.starts_with("seeds:") {
+            let seeds = parse_seeds(header).map_err(|e| e.on_line(header_line))?;
+            almanac.seed_ranges = build_seed_ranges(&seeds).ok_or_else(|| {
+                // there's an odd number of seeds, so there is a last one
+                let last = header.split_whitespace().last().unwrap_or(header);
+                Error::UnpairedSeed(Span::of(header, last).on_line(header_line))
+            })?;
+            // for part 1
+            almanac.seeds = seeds;
+            // the seeds should be on their own
+            if let Some((n, l)) = lines.next() {
+                return Err(Error::UnexpectedLine(Span::of(l, l).on_line(n)));
             }
-        }
-        // handles last map, since there is no extra line after it
-        // very jank
-        if current_map.len() > 0 {
-            current_map.sort();
-            almanac.insert_map(AlmanacMap {
-                ranges: current_map.clone(),
-            });
+        } else if let Some((from, to)) = parse_map_header(header) {
+            let mut ranges: Vec<MapRange> = Vec::new();
+            for (n, l) in lines {
+                ranges.push(parse_map_range(l).map_err(|e| e.on_line(n))?);
+            }
+            almanac.insert_map(AlmanacMap::named(from, to, ranges));
+        } else {
+            return Err(Error::UnexpectedLine(
+                Span::of(header, header).on_line(header_line),
+            ));
         }
     }
-    almanac
+    Ok(almanac)
 }
 
-pub fn parse_map_range(line: &str) -> MapRange {
-    let map_line: Vec<u64> = line
-        .split(' ')
-        .map(|c| u64::from_str_radix(c, 10).expect("Should be a number"))
+fn parse_number(line: &str, number: &str) -> Result<u64, Error> {
+    number
+        .parse::<u64>()
+        .map_err(|_| Error::InvalidNumber(Span::of(line, number)))
+}
+
+// like parse_almanac, but rejects almanacs with overlapping or overflowing ranges instead of
+// giving answers that depend on the order of the ranges
+pub fn parse_almanac_strict<R: BufRead>(reader: R) -> Result<Almanac, Error> {
+    let almanac = parse_almanac(reader)?;
+    let errors: Vec<Issue> = almanac
+        .validate()
+        .into_iter()
+        .filter(Issue::is_error)
         .collect();
-    MapRange::new(map_line[1], map_line[0], map_line[2])
+    if errors.is_empty() {
+        Ok(almanac)
+    } else {
+        Err(Error::Invalid(errors))
+    }
+}
+
+// the source and destination categories from a header like "seed-to-soil map:"
+pub fn parse_map_header(line: &str) -> Option<(&str, &str)> {
+    line.strip_suffix(" map:")?.split_once("-to-")
+}
+
+// a map range is the destination start, source start and length
+pub fn parse_map_range(line: &str) -> Result<MapRange, Error> {
+    let map_line: Vec<u64> = line
+        .split_whitespace()
+        .map(|n| parse_number(line, n))
+        .collect::<Result<Vec<u64>, Error>>()?;
+    match map_line[..] {
+        [dest, source, range] => Ok(MapRange::new(source, dest, range)),
+        _ => Err(Error::InvalidMapRange(Span::of(line, line))),
+    }
 }
 
-pub fn parse_seeds(line: &str) -> Vec<u64> {
-    let line = &line[7..]; // skips the "seeds: " part of the line
-    line.split(' ')
-        .map(|s| u64::from_str_radix(s, 10).expect("Should be a number"))
+pub fn parse_seeds(line: &str) -> Result<Vec<u64>, Error> {
+    let seeds = line
+        .strip_prefix("seeds:")
+        .ok_or_else(|| Error::UnexpectedLine(Span::of(line, line)))?;
+    seeds
+        .split_whitespace()
+        .map(|s| parse_number(line, s))
         .collect()
 }
 
-pub fn build_seed_ranges(seeds: &Vec<u64>) -> Vec<SeedRange> {
+// pairs up the seeds as starts and lengths, or None if the last seed has nothing to pair with
+pub fn build_seed_ranges(seeds: &[u64]) -> Option<Vec<SeedRange>> {
+    if seeds.len() % 2 == 1 {
+        return None;
+    }
     let mut seed_ranges: Vec<SeedRange> = Vec::new();
     seeds.chunks_exact(2).for_each(|chunk| {
         seed_ranges.push(SeedRange {
@@ -215,16 +1145,7 @@ pub fn build_seed_ranges(seeds: &Vec<u64>) -> Vec<SeedRange> {
         })
     });
     seed_ranges.sort();
-    seed_ranges
-}
-
-// shamelessly stolen from Rust By Example
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    Some(seed_ranges)
 }
 
 #[cfg(test)]
@@ -235,14 +1156,14 @@ mod tests {
     fn test_parse_seeds() {
         let input = "seeds: 79 14 55 13";
         let expected: Vec<u64> = vec![79, 14, 55, 13];
-        assert_eq!(expected, parse_seeds(input));
+        assert_eq!(Ok(expected), parse_seeds(input));
     }
 
     #[test]
     fn test_parse_map_range() {
         let input = "50 98 2";
         let expected = MapRange::new(98, 50, 2);
-        assert_eq!(expected, parse_map_range(input));
+        assert_eq!(Ok(expected), parse_map_range(input));
     }
 
     #[test]
@@ -263,66 +1184,513 @@ mod tests {
     fn test_build_seed_ranges() {
         let seeds: Vec<u64> = vec![79, 14, 55, 13];
         let expected: Vec<SeedRange> = vec![SeedRange::new(55, 13), SeedRange::new(79, 14)];
-        assert_eq!(expected, build_seed_ranges(&seeds));
+        assert_eq!(Some(expected), build_seed_ranges(&seeds));
+        assert_eq!(None, build_seed_ranges(&[79, 14, 55]));
     }
 
     #[test]
     fn test_almanac() {
-        let input = "test.txt";
+        let input = aoc_common::open("test.txt").unwrap();
         let almanac = Almanac {
             seeds: vec![79, 14, 55, 13],
             seed_ranges: vec![SeedRange::new(55, 13), SeedRange::new(79, 14)],
             maps: vec![
-                AlmanacMap {
-                    ranges: vec![MapRange::new(50, 52, 48), MapRange::new(98, 50, 2)],
-                },
-                AlmanacMap {
-                    ranges: vec![
+                AlmanacMap::named(
+                    "seed",
+                    "soil",
+                    vec![MapRange::new(50, 52, 48), MapRange::new(98, 50, 2)],
+                ),
+                AlmanacMap::named(
+                    "soil",
+                    "fertilizer",
+                    vec![
                         MapRange::new(0, 39, 15),
                         MapRange::new(15, 0, 37),
                         MapRange::new(52, 37, 2),
                     ],
-                },
-                AlmanacMap {
-                    ranges: vec![
+                ),
+                AlmanacMap::named(
+                    "fertilizer",
+                    "water",
+                    vec![
                         MapRange::new(0, 42, 7),
                         MapRange::new(7, 57, 4),
                         MapRange::new(11, 0, 42),
                         MapRange::new(53, 49, 8),
                     ],
-                },
-                AlmanacMap {
-                    ranges: vec![MapRange::new(18, 88, 7), MapRange::new(25, 18, 70)],
-                },
-                AlmanacMap {
-                    ranges: vec![
+                ),
+                AlmanacMap::named(
+                    "water",
+                    "light",
+                    vec![MapRange::new(18, 88, 7), MapRange::new(25, 18, 70)],
+                ),
+                AlmanacMap::named(
+                    "light",
+                    "temperature",
+                    vec![
                         MapRange::new(45, 81, 19),
                         MapRange::new(64, 68, 13),
                         MapRange::new(77, 45, 23),
                     ],
-                },
-                AlmanacMap {
-                    ranges: vec![MapRange::new(0, 1, 69), MapRange::new(69, 0, 1)],
-                },
-                AlmanacMap {
-                    ranges: vec![MapRange::new(56, 60, 37), MapRange::new(93, 56, 4)],
-                },
+                ),
+                AlmanacMap::named(
+                    "temperature",
+                    "humidity",
+                    vec![MapRange::new(0, 1, 69), MapRange::new(69, 0, 1)],
+                ),
+                AlmanacMap::named(
+                    "humidity",
+                    "location",
+                    vec![MapRange::new(56, 60, 37), MapRange::new(93, 56, 4)],
+                ),
             ],
         };
-        assert_eq!(almanac, parse_almanac(input));
+        assert_eq!(almanac, parse_almanac(input).unwrap());
 
-        assert_eq!(82, almanac.get_location_from_seed(79));
-        assert_eq!(79, almanac.get_seed_from_location(82));
+        assert_eq!(Ok(82), almanac.get_location_from_seed(79));
+        assert_eq!(Ok(79), almanac.get_seed_from_location(82));
         assert!(almanac.contains_seed(80));
         assert!(almanac.contains_seed(62));
         assert!(!almanac.contains_seed(0));
         assert!(!almanac.contains_seed(14));
     }
 
+    #[test]
+    fn test_map_range_splitting() {
+        let map = AlmanacMap::new(vec![MapRange::new(50, 52, 48), MapRange::new(98, 50, 2)]);
+        // entirely inside one map range
+        assert_eq!(
+            vec![SeedRange::new(81, 14)],
+            map.map_range(SeedRange::new(79, 14))
+        );
+        // starts in a gap before the first map range
+        assert_eq!(
+            vec![SeedRange::new(45, 5), SeedRange::new(52, 5)],
+            map.map_range(SeedRange::new(45, 10))
+        );
+        // crosses both map ranges and runs off the end
+        assert_eq!(
+            vec![
+                SeedRange::new(97, 3),
+                SeedRange::new(50, 2),
+                SeedRange::new(100, 5)
+            ],
+            map.map_range(SeedRange::new(95, 10))
+        );
+        // not covered by any map range
+        assert_eq!(
+            vec![SeedRange::new(0, 10)],
+            map.map_range(SeedRange::new(0, 10))
+        );
+        assert_eq!(
+            vec![SeedRange::new(81, 14), SeedRange::new(57, 13)],
+            map.map_ranges(&[SeedRange::new(79, 14), SeedRange::new(55, 13)])
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        let almanac = parse_almanac(aoc_common::open("test.txt").unwrap()).unwrap();
+        let composed = almanac.maps[0].compose(&almanac.maps[1]);
+        assert_eq!(("seed", "fertilizer"), (composed.from(), composed.to()));
+        for n in 0..120 {
+            assert_eq!(
+                almanac.maps[1].lookup(almanac.maps[0].lookup(n)),
+                composed.lookup(n)
+            );
+        }
+        // composing with an empty map changes nothing
+        assert_eq!(
+            almanac.maps[0],
+            almanac.maps[0].compose(&AlmanacMap::default())
+        );
+        assert_eq!(
+            almanac.maps[0],
+            AlmanacMap::default().compose(&almanac.maps[0])
+        );
+
+        let seed_to_location = almanac.seed_to_location().unwrap();
+        let location_to_seed = almanac.location_to_seed().unwrap();
+        assert_eq!(
+            ("seed", "location"),
+            (seed_to_location.from(), seed_to_location.to())
+        );
+        assert_eq!(82, seed_to_location.lookup(79));
+        for n in 0..120 {
+            let location = almanac.get_location_from_seed(n).unwrap();
+            assert_eq!(location, seed_to_location.lookup(n));
+            assert_eq!(n, location_to_seed.lookup(location));
+        }
+    }
+
+    #[test]
+    fn test_binary_search_lookup() {
+        // a thousand ranges of five numbers with gaps of five between them, shuffled around
+        let ranges: Vec<MapRange> = (0..1000)
+            .map(|i| MapRange::new(i * 10, (i * 37 % 1000) * 10 + 5, 5))
+            .collect();
+        let map = AlmanacMap::new(ranges.clone());
+        let linear = |n: u64| ranges.iter().find_map(|r| r.lookup(n)).unwrap_or(n);
+        let linear_reverse = |n: u64| ranges.iter().find_map(|r| r.reverse_lookup(n)).unwrap_or(n);
+        for n in 0..10_020 {
+            assert_eq!(linear(n), map.lookup(n));
+            assert_eq!(linear_reverse(n), map.reverse_lookup(n));
+        }
+
+        let inputs: Vec<u64> = (0..10_020).step_by(3).collect();
+        let expected: Vec<u64> = inputs.iter().map(|n| map.lookup(*n)).collect();
+        assert_eq!(expected, map.lookup_sorted(&inputs));
+        assert_eq!(
+            vec![0, 5, 5],
+            AlmanacMap::default().lookup_sorted(&[0, 5, 5])
+        );
+    }
+
+    #[test]
+    fn test_inverse() {
+        let map = AlmanacMap::new(vec![MapRange::new(98, 50, 2), MapRange::new(50, 52, 48)]);
+        let inverse = map.inverse();
+        assert_eq!(
+            &[MapRange::new(50, 98, 2), MapRange::new(52, 50, 48)],
+            inverse.ranges()
+        );
+        for n in 0..120 {
+            assert_eq!(n, inverse.lookup(map.lookup(n)));
+        }
+    }
+
+    #[test]
+    fn test_map_set() {
+        let map = AlmanacMap::new(vec![MapRange::new(50, 52, 48), MapRange::new(98, 50, 2)]);
+        let set = |ranges: &[(u128, u128)]| -> IntervalSet {
+            ranges
+                .iter()
+                .map(|(s, l)| Interval::with_len(*s, *l))
+                .collect()
+        };
+        assert_eq!(set(&[(50, 2), (97, 8)]), map.map_set(&set(&[(95, 10)])));
+        assert_eq!(set(&[(45, 5), (52, 5)]), map.map_set(&set(&[(45, 10)])));
+        assert_eq!(
+            vec![SeedRange::new(0, 50), SeedRange::new(100, u64::MAX - 99)],
+            map.pass_through()
+        );
+        assert_eq!(set(&[(50, 50)]), map.sources());
+
+        let almanac = parse_almanac(aoc_common::open("test.txt").unwrap()).unwrap();
+        let seeds: IntervalSet = almanac
+            .seed_ranges
+            .iter()
+            .map(SeedRange::interval)
+            .collect();
+        let locations = almanac.convert_set("seed", "location", &seeds).unwrap();
+        assert_eq!(Some(46), locations.min());
+        // the same numbers as converting the ranges one by one
+        let ranges = almanac.location_ranges().unwrap();
+        assert_eq!(
+            locations,
+            ranges
+                .iter()
+                .map(SeedRange::interval)
+                .collect::<IntervalSet>()
+        );
+    }
+
+    #[test]
+    fn test_lowest_seed_range_location() {
+        let almanac = parse_almanac(aoc_common::open("test.txt").unwrap()).unwrap();
+        assert_eq!(Ok((46, 82)), almanac.lowest_seed_range_location());
+        assert_eq!(
+            Err(Error::NoPath {
+                from: String::from("seed"),
+                to: String::from("location")
+            }),
+            Almanac::new().lowest_seed_range_location()
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let almanac = parse_almanac(aoc_common::open("test.txt").unwrap()).unwrap();
+        let steps = almanac.trace("seed", "location", 79).unwrap();
+        assert_eq!(7, steps.len());
+        assert_eq!(
+            TraceStep {
+                from: String::from("seed"),
+                to: String::from("soil"),
+                input: 79,
+                output: 81,
+                range: Some(MapRange::new(50, 52, 48)),
+                reversed: false,
+            },
+            steps[0]
+        );
+        assert_eq!("seed 79 -> soil 81 via 52 50 48", steps[0].to_string());
+        assert_eq!(
+            "soil 81 -> fertilizer 81 via identity",
+            steps[1].to_string()
+        );
+        assert_eq!(Ok(82), almanac.get_location_from_seed(79));
+        assert_eq!(82, steps[6].output);
+
+        let steps = Trace::Location(46).run(&almanac).unwrap();
+        assert_eq!(82, steps[6].output);
+        assert!(steps.iter().all(|s| s.reversed));
+        assert_eq!(
+            "location 46 -> humidity 46 via identity",
+            steps[0].to_string()
+        );
+        assert_eq!(
+            "humidity 46 -> temperature 45 via 1 0 69 reversed",
+            steps[1].to_string()
+        );
+    }
+
+    #[test]
+    fn test_trace_args() {
+        let args = |a: &[&str]| a.iter().map(|a| String::from(*a)).collect::<Vec<_>>();
+        let mut a = args(&["--trace-seed", "79", "--test"]);
+        assert_eq!(Ok(Some(Trace::Seed(79))), Trace::take_from_args(&mut a));
+        assert_eq!(args(&["--test"]), a);
+        let mut a = args(&["input.txt", "--trace-location", "46"]);
+        assert_eq!(Ok(Some(Trace::Location(46))), Trace::take_from_args(&mut a));
+        assert_eq!(args(&["input.txt"]), a);
+        assert_eq!(Ok(None), Trace::take_from_args(&mut args(&["--test"])));
+        assert!(Trace::take_from_args(&mut args(&["--trace-seed"])).is_err());
+        assert!(Trace::take_from_args(&mut args(&["--trace-seed", "x"])).is_err());
+    }
+
+    #[test]
+    fn test_convert() {
+        let almanac = parse_almanac(aoc_common::open("test.txt").unwrap()).unwrap();
+        assert_eq!(
+            vec![
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ],
+            almanac.categories()
+        );
+        // soil 81 is fertilizer 81, water 74, light 74, temperature 78 and humidity 78
+        assert_eq!(Ok(78), almanac.convert("soil", "humidity", 81));
+        assert_eq!(Ok(81), almanac.convert("humidity", "soil", 78));
+        assert_eq!(Ok(81), almanac.convert("soil", "soil", 81));
+        assert_eq!(
+            Ok(vec![SeedRange::new(81, 14)]),
+            almanac.convert_ranges("seed", "soil", &[SeedRange::new(79, 14)])
+        );
+        assert_eq!(
+            Err(Error::NoPath {
+                from: String::from("soil"),
+                to: String::from("weather")
+            }),
+            almanac.convert("soil", "weather", 81)
+        );
+
+        // the maps can be listed in any order
+        let mut shuffled = parse_almanac(aoc_common::open("test.txt").unwrap()).unwrap();
+        shuffled.maps.reverse();
+        shuffled.maps.swap(1, 4);
+        assert_eq!(Ok(82), shuffled.get_location_from_seed(79));
+        assert_eq!(Ok(35), shuffled.lowest_seed_location());
+        assert_eq!(Ok((46, 82)), shuffled.lowest_seed_range_location());
+    }
+
+    #[test]
+    fn test_validate() {
+        let almanac = parse_almanac_strict(aoc_common::open("test.txt").unwrap()).unwrap();
+        // the example maps each cover one block of numbers without gaps
+        assert_eq!(Vec::<Issue>::new(), almanac.validate());
+
+        let map = AlmanacMap::named(
+            "seed",
+            "soil",
+            vec![
+                MapRange::new(0, 100, 20),
+                MapRange::new(10, 200, 5),
+                MapRange::new(30, 105, 5),
+                MapRange::new(50, u64::MAX, 2),
+                MapRange::new(u64::MAX - 1, 40, 2),
+            ],
+        );
+        let map_name = String::from("seed-to-soil");
+        let issues = map.validate();
+        let expected = [
+            Issue::MapRangeOverflow {
+                map: map_name.clone(),
+                range: MapRange::new(50, u64::MAX, 2),
+            },
+            Issue::OverlappingSources {
+                map: map_name.clone(),
+                first: MapRange::new(0, 100, 20),
+                second: MapRange::new(10, 200, 5),
+            },
+            Issue::OverlappingDestinations {
+                map: map_name.clone(),
+                first: MapRange::new(0, 100, 20),
+                second: MapRange::new(30, 105, 5),
+            },
+            Issue::PassThroughCollision {
+                map: map_name.clone(),
+                range: MapRange::new(u64::MAX - 1, 40, 2),
+                numbers: SeedRange::new(40, 2),
+            },
+            Issue::Gap {
+                map: map_name.clone(),
+                numbers: SeedRange::new(20, 10),
+            },
+        ];
+        for issue in expected {
+            assert!(issues.contains(&issue), "{issue} not found in {issues:?}");
+        }
+        // a range can run right up to u64::MAX without overflowing
+        assert!(!issues.contains(&Issue::MapRangeOverflow {
+            map: map_name.clone(),
+            range: MapRange::new(u64::MAX - 1, 40, 2),
+        }));
+        // lookups still work right up to the top without overflowing
+        assert_eq!(41, map.lookup(u64::MAX));
+        assert_eq!(Some(u64::MAX), map.ranges()[4].reverse_lookup(41));
+
+        let input = "seeds: 18446744073709551615 2\n\nseed-to-soil map:\n50 98 2\n52 50 60\n";
+        match parse_almanac_strict(input.as_bytes()) {
+            Err(Error::Invalid(issues)) => assert_eq!(
+                vec![
+                    Issue::SeedRangeOverflow(SeedRange::new(u64::MAX, 2)),
+                    Issue::OverlappingSources {
+                        map: map_name.clone(),
+                        first: MapRange::new(50, 52, 60),
+                        second: MapRange::new(98, 50, 2)
+                    },
+                    Issue::PassThroughCollision {
+                        map: map_name.clone(),
+                        range: MapRange::new(50, 52, 60),
+                        numbers: SeedRange::new(110, 2)
+                    },
+                ],
+                issues
+            ),
+            other => panic!("expected invalid almanac, got {other:?}"),
+        }
+        // the lenient parser still accepts it
+        assert!(parse_almanac(input.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("50 98 2", MapRange::new(98, 50, 2).to_string());
+        assert_eq!("79 14", SeedRange::new(79, 14).to_string());
+        let map = AlmanacMap::named(
+            "seed",
+            "soil",
+            vec![MapRange::new(98, 50, 2), MapRange::new(50, 52, 48)],
+        );
+        assert_eq!("seed-to-soil map:\n52 50 48\n50 98 2", map.to_string());
+
+        let input = "seeds: 79 14 55 13\n\nseed-to-soil map:\n52 50 48\n50 98 2\n\nsoil-to-fertilizer map:\n39 0 15\n";
+        let almanac = parse_almanac(input.as_bytes()).unwrap();
+        assert_eq!(input, almanac.to_string());
+
+        let almanac = parse_almanac(aoc_common::open("test.txt").unwrap()).unwrap();
+        let text = almanac.to_string();
+        assert_eq!(almanac, parse_almanac(text.as_bytes()).unwrap());
+        // composed maps can be written out and read back in too
+        let composed = Almanac {
+            seeds: almanac.seeds.clone(),
+            seed_ranges: almanac.seed_ranges.clone(),
+            maps: vec![almanac.seed_to_location().unwrap()],
+        };
+        let text = composed.to_string();
+        assert!(text.contains("seed-to-location map:"));
+        assert_eq!(composed, parse_almanac(text.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_json() {
+        let range_json = serde_json::to_string(&MapRange::new(98, 50, 2)).unwrap();
+        assert_eq!(r#"{"source":98,"dest":50,"range":2}"#, range_json);
+        let map = AlmanacMap::named("seed", "soil", vec![MapRange::new(98, 50, 2)]);
+        assert_eq!(
+            r#"{"from":"seed","to":"soil","ranges":[{"source":98,"dest":50,"range":2}]}"#,
+            serde_json::to_string(&map).unwrap()
+        );
+        assert_eq!(
+            r#"{"start":79,"length":14}"#,
+            serde_json::to_string(&SeedRange::new(79, 14)).unwrap()
+        );
+
+        let almanac = parse_almanac(aoc_common::open("test.txt").unwrap()).unwrap();
+        let json = almanac.to_json().unwrap();
+        let from_json = Almanac::from_json(&json).unwrap();
+        assert_eq!(almanac, from_json);
+        // the destination index is rebuilt, so reverse lookups still work
+        assert_eq!(Ok(79), from_json.get_seed_from_location(82));
+
+        assert!(!json.contains("seed_ranges"));
+        // seed ranges in the JSON are ignored and worked out from the seeds instead
+        let edited = Almanac::from_json(
+            r#"{"seeds":[79,14],"seed_ranges":[{"start":1,"length":2}],"maps":[]}"#,
+        )
+        .unwrap();
+        assert_eq!(vec![SeedRange::new(79, 14)], edited.seed_ranges);
+
+        assert!(matches!(
+            Almanac::from_json("{\"seeds\": 1}"),
+            Err(Error::Json(_))
+        ));
+        assert!(matches!(
+            Almanac::from_json(r#"{"seeds":[79,14,55],"maps":[]}"#),
+            Err(Error::Json(message)) if message.contains("the last of 3 seeds has no range length")
+        ));
+    }
+
     #[test]
     fn test_seed_range() {
         let seed_range = SeedRange::new(55, 13);
         assert!(seed_range.within(56));
         assert!(!seed_range.within(54));
+        assert_eq!(Interval::new(55, 68), seed_range.interval());
+        assert_eq!(seed_range, SeedRange::from(seed_range.interval()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(Error::InvalidNumber(Span::new(1, 4, "9x"))),
+            parse_map_range("50 9x 2")
+        );
+        assert_eq!(
+            Err(Error::InvalidMapRange(Span::new(1, 1, "50 98"))),
+            parse_map_range("50 98")
+        );
+        assert_eq!(
+            Err(Error::InvalidNumber(Span::new(1, 11, "-14"))),
+            parse_seeds("seeds: 79 -14")
+        );
+
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50\n";
+        assert_eq!(
+            Err(Error::InvalidMapRange(Span::new(5, 1, "52 50"))),
+            parse_almanac(input.as_bytes())
+        );
+        let input = "seeds: 79 14\n\nsoil:\n50 98 2\n";
+        assert_eq!(
+            Err(Error::UnexpectedLine(Span::new(3, 1, "soil:"))),
+            parse_almanac(input.as_bytes())
+        );
+        let input = "\nseeds: 79 14 55\n\nseed-to-soil map:\n50 98 2\n";
+        assert_eq!(
+            Err(Error::UnpairedSeed(Span::new(2, 14, "55"))),
+            parse_almanac(input.as_bytes())
+        );
+        let input = "seeds: 79 14\n\nsoil map:\n50 98 2\n";
+        assert_eq!(
+            Err(Error::UnexpectedLine(Span::new(3, 1, "soil map:"))),
+            parse_almanac(input.as_bytes())
+        );
     }
 }
@@ -1,13 +1,35 @@
-use day_06::{parse_file, product_of_race_records, read_lines};
+use std::process::ExitCode;
 
-fn main() {
-    if let Ok(lines) = read_lines("input.txt") {
-        match parse_file(lines) {
-            Ok((times, distances)) => match product_of_race_records(times, distances) {
-                Some(product) => println!("Product of number of ways to beat race: {}", product),
-                None => println!("Failed to calculate product"),
-            },
-            Err(e) => eprintln!("{e}"),
+use aoc_common::{open_from_args, Solution};
+use day_06::{race_table, Day06, Mode};
+
+fn main() -> ExitCode {
+    let sheet = match open_from_args(env!("CARGO_MANIFEST_DIR"))
+        .map_err(day_06::Error::from)
+        .and_then(Day06::parse)
+    {
+        Ok(sheet) => sheet,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let races = match sheet.races(Mode::Separate) {
+        Ok(races) => races,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("{}\n", race_table(&races));
+    match Day06::part1(&sheet) {
+        Ok(product) => {
+            println!("Product of number of ways to beat race: {product}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
         }
     }
 }
@@ -1,22 +1,29 @@
-use day_06::{beat_race_record, combine_nums, parse_file, read_lines};
+use std::process::ExitCode;
 
-fn main() {
-    if let Ok(lines) = read_lines("input.txt") {
-        match parse_file(lines) {
-            Ok((times, distances)) => {
-                // combine numbers into single number for each vector
-                if (times.len() == 0) || (distances.len() == 0) {
-                    eprintln!("No times or distances to process");
-                } else {
-                    let time = combine_nums(times);
-                    let distance = combine_nums(distances);
-                    match beat_race_record(time, distance) {
-                        Ok(record) => println!("Number of ways to beat record: {record}"),
-                        Err(e) => eprintln!("{e}"),
-                    }
-                }
-            }
-            Err(e) => eprintln!("{e}"),
+use aoc_common::{open_from_args, Solution};
+use day_06::{beat_race_record, race_table, Day06};
+
+fn main() -> ExitCode {
+    let race = match open_from_args(env!("CARGO_MANIFEST_DIR"))
+        .map_err(day_06::Error::from)
+        .and_then(Day06::parse)
+        .and_then(|sheet| sheet.kerned_race())
+    {
+        Ok(race) => race,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("{}\n", race_table(&[race]));
+    match beat_race_record(race.time, race.record) {
+        Ok(record) => {
+            println!("Number of ways to beat record: {record}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
         }
     }
 }
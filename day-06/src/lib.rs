@@ -1,116 +1,399 @@
-use std::{
-    cmp::Ordering,
-    fs::File,
-    io::{self, BufRead, BufReader, Lines},
-    iter::zip,
-    path::Path,
+use std::{fmt, io::BufRead, iter::zip, ops::RangeInclusive};
+
+use aoc_common::{read_lines, InputError, Solution, Span};
+
+mod physics;
+mod roots;
+
+pub use physics::{
+    count_wins, search_winning_holds, Accelerating, ChargeRate, Linear, Physics, SpeedCap,
+    StartupDelay,
 };
+pub use roots::{solve_quadratic, Float, Roots};
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+
+    type Input = Sheet;
+    type Answer = u64;
+    type Error = Error;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, Self::Error> {
+        parse_sheet(reader)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        product_of_race_records(input.times(), input.distances()).ok_or(Error::NoRaces)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        let race = input.kerned_race()?;
+        beat_race_record(race.time, race.record)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    Input(InputError),
+    InvalidNumber(Span),
+    // the sheet is missing its times or distances
+    NoRaces,
+    NoSolution {
+        time: u64,
+        distance: u64,
+    },
+    // the numbers on a row joined together are too big for a u64
+    InvalidKernedNumber(Span),
+    // no "Time:" or "Distance:" line by the end of the sheet, where line is its last line
+    MissingRow {
+        label: String,
+        line: usize,
+    },
+    // a second "Time:" or "Distance:" line
+    DuplicateRow(Span),
+    // a line that isn't either row
+    UnexpectedLine(Span),
+    // the rows have different numbers of races, where line is the second of the two rows
+    MismatchedRows {
+        line: usize,
+        times: usize,
+        distances: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(e) => write!(f, "{e}"),
+            Self::InvalidNumber(span) => write!(f, "invalid number {span}"),
+            Self::NoRaces => write!(f, "no times or distances to process"),
+            Self::NoSolution { time, distance } => write!(
+                f,
+                "no solution found for race of {time}ms with record {distance}mm"
+            ),
+            Self::InvalidKernedNumber(span) => write!(f, "kerned number {span} is too large"),
+            Self::MissingRow { label, line } => {
+                write!(f, "no {label} row in the sheet, which ends on line {line}")
+            }
+            Self::DuplicateRow(span) => write!(f, "duplicate row {span}"),
+            Self::UnexpectedLine(span) => write!(f, "unexpected line {span}"),
+            Self::MismatchedRows {
+                line,
+                times,
+                distances,
+            } => write!(
+                f,
+                "{times} times but {distances} distances, found on line {line}"
+            ),
+        }
+    }
+}
 
-pub fn combine_nums(nums: Vec<f64>) -> f64 {
-    let mut new_num = String::new();
-    for n in nums {
-        new_num.push_str(n.to_string().as_str());
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Input(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::InvalidNumber(span) => Self::InvalidNumber(span.on_line(line)),
+            Self::InvalidKernedNumber(span) => Self::InvalidKernedNumber(span.on_line(line)),
+            Self::DuplicateRow(span) => Self::DuplicateRow(span.on_line(line)),
+            Self::UnexpectedLine(span) => Self::UnexpectedLine(span.on_line(line)),
+            e => e,
+        }
     }
-    new_num.parse::<f64>().unwrap()
 }
 
-pub fn product_of_race_records(times: Vec<f64>, distances: Vec<f64>) -> Option<f64> {
-    if (times.len() == 0) || (distances.len() == 0) {
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Self::Input(e)
+    }
+}
+
+// joins the digits of the numbers together, e.g. [21, 35] becomes 2135
+pub fn combine_nums(nums: &[u64]) -> Option<u64> {
+    nums.iter().try_fold(0u64, |combined, n| {
+        let digits = n.checked_ilog10().unwrap_or(0) + 1;
+        combined
+            .checked_mul(10u64.checked_pow(digits)?)?
+            .checked_add(*n)
+    })
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Race {
+    // how long the race lasts in ms
+    pub time: u64,
+    // the furthest anyone has gone in mm
+    pub record: u64,
+}
+
+impl Race {
+    pub fn new(time: u64, record: u64) -> Self {
+        Self { time, record }
+    }
+
+    // how far the boat goes when the button is held for hold ms, which can be more than a u64
+    // holds for long races
+    pub fn distance(&self, hold: u64) -> u128 {
+        Linear.distance(self.time, hold)
+    }
+
+    // the first and last hold times that beat the record, or none if it can't be beaten
+    pub fn winning_holds(&self) -> Option<RangeInclusive<u64>> {
+        Linear.winning_holds(self)
+    }
+
+    // the same as winning_holds, but with a different model of how the boat moves
+    pub fn winning_holds_with<P: Physics>(&self, physics: &P) -> Option<RangeInclusive<u64>> {
+        physics.winning_holds(self)
+    }
+
+    pub fn ways_to_win(&self) -> u64 {
+        count_wins(&Linear, self)
+    }
+
+    // holding for half the race goes the furthest
+    pub fn best_hold(&self) -> u64 {
+        Linear.best_hold(self.time)
+    }
+
+    // how much further than the record the best hold goes, or none if it can't be beaten
+    pub fn margin(&self) -> Option<u128> {
+        self.distance(self.best_hold())
+            .checked_sub(self.record as u128)
+            .filter(|margin| *margin > 0)
+    }
+}
+
+// pairs up each time with its record
+pub fn races(times: &[u64], distances: &[u64]) -> Vec<Race> {
+    zip(times, distances)
+        .map(|(time, record)| Race::new(*time, *record))
+        .collect()
+}
+
+// a table with the winning hold times for each race, for the part binaries to print
+pub fn race_table(races: &[Race]) -> String {
+    let mut table =
+        String::from("| Time | Record | First | Last | Ways | Margin |\n|---|---|---|---|---|---|");
+    for race in races {
+        let (first, last, margin) = match (race.winning_holds(), race.margin()) {
+            (Some(holds), Some(margin)) => (
+                holds.start().to_string(),
+                holds.end().to_string(),
+                margin.to_string(),
+            ),
+            _ => (String::from("-"), String::from("-"), String::from("-")),
+        };
+        table.push_str(&format!(
+            "\n| {} | {} | {first} | {last} | {} | {margin} |",
+            race.time,
+            race.record,
+            race.ways_to_win()
+        ));
+    }
+    table
+}
+
+pub fn product_of_race_records(times: &[u64], distances: &[u64]) -> Option<u64> {
+    if times.is_empty() || distances.is_empty() {
         None
     } else {
-        let product: f64 = zip(times, distances)
-            .map(|x| beat_race_record(x.0, x.1).unwrap_or(1.0))
-            .reduce(|x, y| x * y)
-            .unwrap();
+        let product: u64 = zip(times, distances)
+            .map(|x| beat_race_record(*x.0, *x.1).unwrap_or(1))
+            .product();
         Some(product)
     }
 }
 
-pub fn parse_file(lines: Lines<BufReader<File>>) -> Result<(Vec<f64>, Vec<f64>), &'static str> {
-    //let lines = read_to_string(filename).expect("Failed to read file");
-    let mut times = vec![];
-    let mut distances = vec![];
-    for line in lines {
-        match line {
-            Ok(l) => {
-                if l.starts_with("Time: ") {
-                    times = parse_line(&l[5..])?;
-                } else if l.starts_with("Distance:") {
-                    distances = parse_line(&l[9..])?;
-                }
-            }
-            Err(_) => return Err("Could not read line"),
+// how the numbers on the sheet are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // each column is a separate race, as in part 1
+    Separate,
+    // each row is one number with bad kerning, as in part 2
+    Kerned,
+}
+
+// one row of the sheet along with where its numbers are, for kerned errors
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    numbers: Vec<u64>,
+    // from the first number on the row to the last, including the spaces between them
+    digits: Span,
+}
+
+impl Row {
+    // the numbers written as one, keeping any leading zeros after the first number
+    fn kerned(&self) -> Result<u64, Error> {
+        let digits: String = self.digits.text.split_whitespace().collect();
+        digits
+            .parse::<u64>()
+            .map_err(|_| Error::InvalidKernedNumber(self.digits.clone()))
+    }
+}
+
+// the race sheet, which is checked to have both rows with a number for every race
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sheet {
+    times: Row,
+    distances: Row,
+}
+
+impl Sheet {
+    pub fn times(&self) -> &[u64] {
+        &self.times.numbers
+    }
+
+    pub fn distances(&self) -> &[u64] {
+        &self.distances.numbers
+    }
+
+    // for part 2, the numbers on each row are actually one number with bad kerning
+    pub fn kerned_race(&self) -> Result<Race, Error> {
+        Ok(Race::new(self.times.kerned()?, self.distances.kerned()?))
+    }
+
+    pub fn races(&self, mode: Mode) -> Result<Vec<Race>, Error> {
+        match mode {
+            Mode::Separate => Ok(races(self.times(), self.distances())),
+            Mode::Kerned => Ok(vec![self.kerned_race()?]),
         }
     }
-    Ok((times, distances))
-}
-
-// shamelessly stolen from Rust By Example
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-pub fn parse_line(line: &str) -> Result<Vec<f64>, &'static str> {
-    Ok(line
-        .split(' ')
-        .filter(|c| !c.is_empty())
-        .map(|s| s.parse::<f64>().expect("Failed to parse number in list"))
-        .collect())
-}
-
-// equation of race
-// let T be time limit of race
-// let D be the distance to travel
-// let t be the time button is held
-// since distance = speed * time
-// and speed is equal to t
-// and time is equal to T - t
-// D = t * (T - t)
-// or -t^2 + Tt - D = 0
-// to beat the race we need the values of t where
-// Tt - t^2 > D
-pub fn beat_race_record(time: f64, distance: f64) -> Result<f64, &'static str> {
-    match solve_quadratic(-1.0, time, -distance) {
-        Some(roots) => {
-            if roots.0 == roots.1 {
-                Ok(1.0)
-            // if both roots are integers, subtract 1 to ensure we don't include lower bound as an answer
-            } else if (roots.0.ceil() == roots.0) && (roots.1.ceil() == roots.1) {
-                Ok((roots.0 - roots.1).abs() - 1.0)
-            } else {
-                Ok((roots.0.ceil() - roots.1.ceil()).abs())
-            }
+}
+
+pub fn parse_sheet<R: BufRead>(reader: R) -> Result<Sheet, Error> {
+    let mut times: Option<(usize, Row)> = None;
+    let mut distances: Option<(usize, Row)> = None;
+    let lines = read_lines(reader)?;
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
         }
-        None => Err("No solution found"),
-    }
-}
-
-// doesn't handle imaginary roots
-pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Option<(f64, f64)> {
-    let discriminant: f64 = (b * b) - 4.0 * a * c;
-    match discriminant.partial_cmp(&0.0) {
-        Some(order) => match order {
-            // roots are imaginary
-            Ordering::Less => None,
-            // 1 real solution
-            Ordering::Equal => {
-                let root1 = -b / (2.0 * a);
-                let root2 = root1;
-                Some((root1, root2))
-            }
-            // 2 real solutions
-            Ordering::Greater => {
-                let root1 = (-b + discriminant.sqrt()) / (2.0 * a);
-                let root2 = (-b - discriminant.sqrt()) / (2.0 * a);
-                Some((root1, root2))
+        let row = match line.split_once(':') {
+            Some(("Time", _)) => &mut times,
+            Some(("Distance", _)) => &mut distances,
+            _ => return Err(Error::UnexpectedLine(Span::new(i + 1, 1, line))),
+        };
+        if row.is_some() {
+            return Err(Error::DuplicateRow(Span::new(i + 1, 1, line)));
+        }
+        *row = Some((i + 1, parse_row(line).map_err(|e| e.on_line(i + 1))?));
+    }
+    let missing = |label: &str| Error::MissingRow {
+        label: String::from(label),
+        line: lines.len(),
+    };
+    let (times_line, times) = times.ok_or_else(|| missing("Time"))?;
+    let (distances_line, distances) = distances.ok_or_else(|| missing("Distance"))?;
+    if times.numbers.is_empty() || distances.numbers.is_empty() {
+        return Err(Error::NoRaces);
+    }
+    if times.numbers.len() != distances.numbers.len() {
+        return Err(Error::MismatchedRows {
+            line: times_line.max(distances_line),
+            times: times.numbers.len(),
+            distances: distances.numbers.len(),
+        });
+    }
+    Ok(Sheet { times, distances })
+}
+
+// reads the races from a sheet in one go
+pub fn parse_races<R: BufRead>(reader: R, mode: Mode) -> Result<Vec<Race>, Error> {
+    parse_sheet(reader)?.races(mode)
+}
+
+pub fn parse_file<R: BufRead>(reader: R) -> Result<(Vec<u64>, Vec<u64>), Error> {
+    let sheet = parse_sheet(reader)?;
+    Ok((sheet.times.numbers, sheet.distances.numbers))
+}
+
+fn parse_row(line: &str) -> Result<Row, Error> {
+    let numbers = line.split_once(':').map_or(line, |(_, n)| n);
+    Ok(Row {
+        numbers: parse_line(line)?,
+        digits: Span::of(line, numbers.trim()),
+    })
+}
+
+// parses the numbers on a line, skipping over a label like "Time:" if there is one
+pub fn parse_line(line: &str) -> Result<Vec<u64>, Error> {
+    let numbers = line.split_once(':').map_or(line, |(_, n)| n);
+    numbers
+        .split_whitespace()
+        .map(|s| {
+            s.parse::<u64>()
+                .map_err(|_| Error::InvalidNumber(Span::of(line, s)))
+        })
+        .collect()
+}
+
+// the number of hold times that beat the record. the lower root of -t^2 + Tt - D = 0 is about
+// where the winning holds start, but f64 can be a long way out once the numbers get past 2^53, so
+// it is only a guess that the exact first win is searched for from. the winning holds are
+// symmetric, so the last is as far below T as the first is above 0
+pub fn beat_race_record(time: u64, distance: u64) -> Result<u64, Error> {
+    let race = Race::new(time, distance);
+    let best = race.best_hold();
+    let wins = |hold: u64| race.distance(hold) > distance as u128;
+    if !wins(best) {
+        return Err(Error::NoSolution { time, distance });
+    }
+    let guess = match solve_quadratic(-1.0, time as f64, -(distance as f64)) {
+        // casting saturates, so a negative root becomes 0
+        Roots::Two(low, _) | Roots::Repeated(low) => (low.ceil() as u64).min(best),
+        _ => best,
+    };
+    // step out from the guess in bigger and bigger steps until lo loses and hi wins, then
+    // binary search between them
+    let (mut lo, mut hi) = (guess, guess);
+    let mut step: u64 = 1;
+    while !wins(hi) {
+        lo = hi;
+        hi = hi.saturating_add(step).min(best);
+        step = step.saturating_mul(2);
+    }
+    while lo > 0 && wins(lo) {
+        hi = lo;
+        lo = lo.saturating_sub(step);
+        step = step.saturating_mul(2);
+    }
+    let first = match wins(lo) {
+        true => lo,
+        false => {
+            while hi - lo > 1 {
+                let mid = lo + (hi - lo) / 2;
+                match wins(mid) {
+                    true => hi = mid,
+                    false => lo = mid,
+                }
             }
-        },
-        // if we got NAN, somehow
-        None => None,
+            hi
+        }
+    };
+    Ok(time - first - first + 1)
+}
+
+// the original floating point version, which is kept to check the exact one against. it loses
+// precision once the numbers get past 2^53, and counts a race that can only be tied as one win
+pub fn beat_race_record_f64(time: f64, distance: f64) -> Option<f64> {
+    match solve_quadratic(-1.0, time, -distance) {
+        Roots::Repeated(_) => Some(1.0),
+        // if both roots are integers, subtract 1 to ensure we don't include lower bound as an answer
+        Roots::Two(low, high) if low.ceil() == low && high.ceil() == high => Some(high - low - 1.0),
+        Roots::Two(low, high) => Some(high.ceil() - low.ceil()),
+        // a is always -1, so there are either real roots or the record can't be reached
+        _ => None,
     }
 }
 
@@ -120,38 +403,207 @@ mod tests {
 
     #[test]
     fn test_solve_quadratic() {
-        assert_eq!(Some((10.0, 20.0)), solve_quadratic(-1.0, 30.0, -200.0));
+        assert_eq!(Roots::Two(10.0, 20.0), solve_quadratic(-1.0, 30.0, -200.0));
     }
 
     #[test]
     fn test_parse_line() {
+        assert_eq!(Ok(vec![21, 35, 78, 99]), parse_line("21   35   78   99"),);
+    }
+
+    #[test]
+    fn test_combine_nums() {
+        assert_eq!(Some(21357899), combine_nums(&[21, 35, 78, 99]));
         assert_eq!(
-            Ok(vec![21.0, 35.0, 78.0, 99.0]),
-            parse_line("21   35   78   99"),
+            Some(400121310111540),
+            combine_nums(&[400, 1213, 1011, 1540])
         );
+        assert_eq!(Some(1000), combine_nums(&[10, 0, 0]));
+        assert_eq!(None, combine_nums(&[u64::MAX, 1]));
     }
 
     #[test]
-    fn test_combine_nums() {
-        assert_eq!(21357899.0, combine_nums(vec![21.0, 35.0, 78.0, 99.0]));
+    fn test_parse_file() {
+        let input = aoc_common::open("test.txt").unwrap();
+        assert_eq!(Ok((vec![7, 15, 30], vec![9, 40, 200])), parse_file(input));
+    }
+
+    #[test]
+    fn test_parse_sheet() {
+        let sheet = parse_sheet(aoc_common::open("test.txt").unwrap()).unwrap();
+        assert_eq!(&[7, 15, 30], sheet.times());
         assert_eq!(
-            400121310111540.0,
-            combine_nums(vec![400.0, 1213.0, 1011.0, 1540.0])
+            Ok(vec![Race::new(7, 9), Race::new(15, 40), Race::new(30, 200)]),
+            sheet.races(Mode::Separate)
+        );
+        assert_eq!(
+            Ok(vec![Race::new(71530, 940200)]),
+            sheet.races(Mode::Kerned)
+        );
+        // blank lines are fine, and leading zeros still count once the numbers are joined up
+        let input = "\nTime: 1 05\n\nDistance: 2 0\n";
+        assert_eq!(
+            Ok(vec![Race::new(105, 20)]),
+            parse_races(input.as_bytes(), Mode::Kerned)
         );
     }
 
     #[test]
-    fn test_product_of_race_records() {
+    fn test_sheet_errors() {
+        let parse = |input: &str| parse_sheet(input.as_bytes());
         assert_eq!(
-            Some(288.0),
-            product_of_race_records(vec![7.0, 15.0, 30.0], vec![9.0, 40.0, 200.0])
+            Err(Error::MissingRow {
+                label: String::from("Distance"),
+                line: 1
+            }),
+            parse("Time: 7 15")
         );
         assert_eq!(
-            Some(1660968.0),
-            product_of_race_records(
-                vec![47.0, 98.0, 66.0, 98.0],
-                vec![400.0, 1213.0, 1011.0, 1540.0]
+            Err(Error::MissingRow {
+                label: String::from("Time"),
+                line: 2
+            }),
+            parse(
+                "Distance: 9 40
+
+"
             )
+        );
+        assert_eq!(Err(Error::NoRaces), parse("Time:\nDistance:"));
+        assert_eq!(
+            Err(Error::MismatchedRows {
+                line: 2,
+                times: 3,
+                distances: 2
+            }),
+            parse("Time: 7 15 30\nDistance: 9 40")
+        );
+        assert_eq!(
+            Err(Error::DuplicateRow(Span::new(3, 1, "Time: 8"))),
+            parse("Time: 7\nDistance: 9\nTime: 8")
+        );
+        assert_eq!(
+            Err(Error::UnexpectedLine(Span::new(2, 1, "Speed: 3"))),
+            parse("Time: 7\nSpeed: 3\nDistance: 9")
+        );
+        let sheet = parse("Time: 99999999999 99999999999\nDistance: 1 2").unwrap();
+        assert_eq!(
+            Err(Error::InvalidKernedNumber(Span::new(
+                1,
+                7,
+                "99999999999 99999999999"
+            ))),
+            sheet.races(Mode::Kerned)
+        );
+        assert_eq!(2, sheet.races(Mode::Separate).unwrap().len());
+    }
+
+    #[test]
+    fn test_product_of_race_records() {
+        assert_eq!(
+            Some(288),
+            product_of_race_records(&[7, 15, 30], &[9, 40, 200])
+        );
+        assert_eq!(
+            Some(1660968),
+            product_of_race_records(&[47, 98, 66, 98], &[400, 1213, 1011, 1540])
         )
     }
+
+    #[test]
+    fn test_beat_race_record() {
+        assert_eq!(Ok(4), beat_race_record(7, 9));
+        assert_eq!(Ok(8), beat_race_record(15, 40));
+        // both roots are whole numbers, 10 and 20, which only tie the record
+        assert_eq!(Ok(9), beat_race_record(30, 200));
+        assert_eq!(Ok(71503), beat_race_record(71530, 940200));
+        // the best possible is holding for 2ms to go 4mm, which only ties
+        assert!(beat_race_record(4, 4).is_err());
+        assert_eq!(Ok(1), beat_race_record(4, 3));
+        assert_eq!(Ok(u64::MAX - 1), beat_race_record(u64::MAX, 0));
+        // past 2^53 the f64 version can't tell these apart
+        let time = (1 << 60) + 1;
+        assert_eq!(Ok(time - 1), beat_race_record(time, 0));
+        assert_eq!(Ok(time - 3), beat_race_record(time, time - 1));
+        // one short of the best distance, where the f64 roots are furthest out
+        let time = (1 << 32) + 1;
+        let best = (1 << 31) * ((1 << 31) + 1);
+        assert_eq!(Ok(2), beat_race_record(time, best - 1));
+        assert!(beat_race_record(time, best).is_err());
+        for record in [0, 1, best / 3, best - 1_000_000, best - 12345] {
+            assert_eq!(
+                Ok(Race::new(time, record).ways_to_win()),
+                beat_race_record(time, record)
+            );
+        }
+    }
+
+    #[test]
+    fn test_race() {
+        let race = Race::new(30, 200);
+        assert_eq!(Some(11..=19), race.winning_holds());
+        assert_eq!(9, race.ways_to_win());
+        assert_eq!(15, race.best_hold());
+        assert_eq!(Some(25), race.margin());
+        assert_eq!(200, race.distance(10));
+
+        let race = Race::new(4, 4);
+        assert_eq!(None, race.winning_holds());
+        assert_eq!(0, race.ways_to_win());
+        assert_eq!(None, race.margin());
+
+        assert_eq!(
+            Some(u128::from(u64::MAX / 2) * u128::from(u64::MAX - u64::MAX / 2)),
+            Race::new(u64::MAX, 0).margin()
+        );
+    }
+
+    #[test]
+    fn test_race_table() {
+        let races = races(&[7, 4], &[9, 4]);
+        assert_eq!(
+            "| Time | Record | First | Last | Ways | Margin |\n\
+             |---|---|---|---|---|---|\n\
+             | 7 | 9 | 2 | 5 | 4 | 3 |\n\
+             | 4 | 4 | - | - | 0 | - |",
+            race_table(&races)
+        );
+    }
+
+    #[test]
+    fn test_f64_cross_check() {
+        for time in 0..200u64 {
+            for distance in 0..=(time * time / 4 + 1) {
+                // the f64 version counts a tie as a win when the roots are the same
+                if time * time == 4 * distance {
+                    continue;
+                }
+                assert_eq!(
+                    beat_race_record(time, distance).unwrap_or(0),
+                    beat_race_record_f64(time as f64, distance as f64).map_or(0, |n| n as u64),
+                    "race of {time}ms with record {distance}mm"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(Error::InvalidNumber(Span::new(1, 15, "1O"))),
+            parse_line("Time:      7  1O   30")
+        );
+        let input = "Time:      7  15   30\nDistance:  9  40  2OO";
+        assert_eq!(
+            Err(Error::InvalidNumber(Span::new(2, 19, "2OO"))),
+            parse_file(input.as_bytes())
+        );
+        assert_eq!(
+            Err(Error::NoSolution {
+                time: 4,
+                distance: 10
+            }),
+            beat_race_record(4, 10)
+        );
+    }
 }
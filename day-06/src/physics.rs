@@ -0,0 +1,228 @@
+use std::ops::RangeInclusive;
+
+use crate::Race;
+
+// how far a boat goes for a given hold time. the distance has to go up (or stay the same) as the
+// hold gets closer to best_hold and down after it, which is what lets the winning holds be
+// searched for instead of trying every one
+pub trait Physics {
+    fn distance(&self, time: u64, hold: u64) -> u128;
+
+    // the hold time that goes the furthest
+    fn best_hold(&self, time: u64) -> u64;
+
+    // the first and last hold times that beat the record, or none if it can't be beaten. models
+    // with a closed form can work this out directly, otherwise it is searched for
+    fn winning_holds(&self, race: &Race) -> Option<RangeInclusive<u64>> {
+        search_winning_holds(self, race)
+    }
+}
+
+// finds the winning holds with a binary search either side of the best hold
+pub fn search_winning_holds<P: Physics + ?Sized>(
+    physics: &P,
+    race: &Race,
+) -> Option<RangeInclusive<u64>> {
+    let wins = |hold: u64| physics.distance(race.time, hold) > race.record as u128;
+    let best = physics.best_hold(race.time).min(race.time);
+    if !wins(best) {
+        return None;
+    }
+    // the first hold in lo..=hi where done is true, given it's true for hi and once it's true it
+    // stays true
+    let first_where = |mut lo: u64, mut hi: u64, done: &dyn Fn(u64) -> bool| {
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if done(mid) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        lo
+    };
+    let first = first_where(0, best, &wins);
+    // the first loss after the best hold is one past the last win
+    let last = match wins(race.time) {
+        true => race.time,
+        false => first_where(best, race.time, &|hold| !wins(hold)) - 1,
+    };
+    Some(first..=last)
+}
+
+// the puzzle's model, where each ms holding the button adds 1mm/ms of speed
+pub struct Linear;
+
+impl Physics for Linear {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        let hold = hold.min(time);
+        hold as u128 * (time - hold) as u128
+    }
+
+    fn best_hold(&self, time: u64) -> u64 {
+        time / 2
+    }
+
+    // let T be the time and D the record. holding for t goes t * (T - t), so we need the values
+    // of t where Tt - t^2 > D. the roots of that are (T ± sqrt(T^2 - 4D)) / 2, and everything
+    // strictly between them wins. this works them out with an integer square root, which is only
+    // out by at most one from the real root, then steps to the first hold time that actually
+    // wins. the winning times are symmetric, so the last is as far below T as the first is
+    // above 0
+    fn winning_holds(&self, race: &Race) -> Option<RangeInclusive<u64>> {
+        let (t, d) = (race.time as u128, race.record as u128);
+        let wins = |hold: u128| hold * (t - hold) > d;
+        // the furthest the boat can go is (T/2)^2, so anything further than that can't be beaten
+        let discriminant = (t * t).checked_sub(4 * d).filter(|disc| *disc > 0)?;
+        let mut first = (t - discriminant.isqrt()) / 2;
+        while first <= t / 2 && !wins(first) {
+            first += 1;
+        }
+        if first > t / 2 {
+            return None;
+        }
+        while first > 0 && wins(first - 1) {
+            first -= 1;
+        }
+        Some(first as u64..=(t - first) as u64)
+    }
+}
+
+// each ms holding the button adds rate mm/ms of speed
+pub struct ChargeRate(pub u64);
+
+impl Physics for ChargeRate {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        self.0 as u128 * Linear.distance(time, hold)
+    }
+
+    fn best_hold(&self, time: u64) -> u64 {
+        time / 2
+    }
+
+    // rate * x > D is the same as x > D / rate rounded down, so this is the linear model with a
+    // smaller record
+    fn winning_holds(&self, race: &Race) -> Option<RangeInclusive<u64>> {
+        let record = race.record.checked_div(self.0)?;
+        Linear.winning_holds(&Race::new(race.time, record))
+    }
+}
+
+// the boat can't go faster than the cap, so holding any longer than that just wastes time
+pub struct SpeedCap(pub u64);
+
+impl Physics for SpeedCap {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        let hold = hold.min(time);
+        hold.min(self.0) as u128 * (time - hold) as u128
+    }
+
+    fn best_hold(&self, time: u64) -> u64 {
+        (time / 2).min(self.0)
+    }
+}
+
+// the speed goes up with the square of the hold time, so the boat goes hold^2 * (T - hold)
+pub struct Accelerating;
+
+impl Physics for Accelerating {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        let hold = hold.min(time);
+        // this can go past a u128 for long races, but anything that big beats any record
+        (hold as u128)
+            .saturating_mul(hold as u128)
+            .saturating_mul((time - hold) as u128)
+    }
+
+    // the peak of t^2 * (T - t) is at 2T/3, which is between the two whole numbers either side
+    fn best_hold(&self, time: u64) -> u64 {
+        let below = (time as u128 * 2 / 3) as u64;
+        let above = (below + 1).min(time);
+        match self.distance(time, above) > self.distance(time, below) {
+            true => above,
+            false => below,
+        }
+    }
+}
+
+// the boat sits still for the delay after the button is let go, which is the same as the linear
+// model in a shorter race
+pub struct StartupDelay(pub u64);
+
+impl Physics for StartupDelay {
+    fn distance(&self, time: u64, hold: u64) -> u128 {
+        Linear.distance(time.saturating_sub(self.0), hold)
+    }
+
+    fn best_hold(&self, time: u64) -> u64 {
+        time.saturating_sub(self.0) / 2
+    }
+
+    fn winning_holds(&self, race: &Race) -> Option<RangeInclusive<u64>> {
+        Linear.winning_holds(&Race::new(race.time.saturating_sub(self.0), race.record))
+    }
+}
+
+// the number of hold times that beat the record with any model
+pub fn count_wins<P: Physics + ?Sized>(physics: &P, race: &Race) -> u64 {
+    physics
+        .winning_holds(race)
+        .map_or(0, |holds| holds.end() - holds.start() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every hold time that wins, by trying them all
+    fn brute_force<P: Physics>(physics: &P, race: &Race) -> Option<RangeInclusive<u64>> {
+        let wins: Vec<u64> = (0..=race.time)
+            .filter(|hold| physics.distance(race.time, *hold) > race.record as u128)
+            .collect();
+        Some(*wins.first()?..=*wins.last()?)
+    }
+
+    fn check<P: Physics>(physics: &P) {
+        for time in 0..60 {
+            for record in (0..2000).step_by(7) {
+                let race = Race::new(time, record);
+                let expected = brute_force(physics, &race);
+                assert_eq!(expected, physics.winning_holds(&race), "{race:?}");
+                assert_eq!(expected, search_winning_holds(physics, &race), "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_models() {
+        check(&Linear);
+        check(&ChargeRate(3));
+        check(&SpeedCap(5));
+        check(&Accelerating);
+        check(&StartupDelay(4));
+    }
+
+    #[test]
+    fn test_count_wins() {
+        let race = Race::new(30, 200);
+        assert_eq!(9, count_wins(&Linear, &race));
+        // 3 * t * (30 - t) > 200 for t from 3 to 27
+        assert_eq!(25, count_wins(&ChargeRate(3), &race));
+        assert_eq!(0, count_wins(&ChargeRate(0), &race));
+        // a cap of 8 goes 8 * 22 = 176 at best
+        assert_eq!(0, count_wins(&SpeedCap(8), &race));
+        assert_eq!(
+            Some(4..=21),
+            SpeedCap(12).winning_holds(&Race::new(30, 100))
+        );
+        assert_eq!(20, Accelerating.best_hold(30));
+        assert_eq!(1, count_wins(&StartupDelay(10), &Race::new(30, 99)));
+
+        let race = Race::new(u64::MAX, u64::MAX);
+        assert_eq!(
+            count_wins(&Linear, &race),
+            search_winning_holds(&Linear, &race).map_or(0, |h| h.end() - h.start() + 1)
+        );
+        assert!(count_wins(&Accelerating, &race) > 0);
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    fmt::Debug,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+// the parts of f32 and f64 the solver needs, so it works with either
+pub trait Float:
+    Copy
+    + Debug
+    + PartialOrd
+    + From<u8>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn copysign(self, sign: Self) -> Self;
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {
+        $(impl Float for $t {
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn copysign(self, sign: Self) -> Self {
+                <$t>::copysign(self, sign)
+            }
+
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+        })*
+    };
+}
+
+impl_float!(f32, f64);
+
+// the solutions to ax^2 + bx + c = 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Roots<T> {
+    // two different real roots, smallest first
+    Two(T, T),
+    // the curve only touches zero at one point
+    Repeated(T),
+    // re ± im·i, where im is always positive
+    Complex { re: T, im: T },
+    // a is 0, so it is just bx + c = 0
+    Linear(T),
+    // a and b are 0 but c isn't, or one of them is NaN
+    None,
+    // a, b and c are all 0, so every x is a root
+    Infinite,
+}
+
+impl<T: Float> Roots<T> {
+    // the real roots, smallest first
+    pub fn real(&self) -> Vec<T> {
+        match *self {
+            Self::Two(x1, x2) => vec![x1, x2],
+            Self::Repeated(x) | Self::Linear(x) => vec![x],
+            Self::Complex { .. } | Self::None | Self::Infinite => vec![],
+        }
+    }
+}
+
+pub fn solve_quadratic<T: Float>(a: T, b: T, c: T) -> Roots<T> {
+    let zero = T::from(0);
+    let two = T::from(2);
+    if a.is_nan() || b.is_nan() || c.is_nan() {
+        return Roots::None;
+    }
+    if a == zero {
+        return match (b == zero, c == zero) {
+            (false, _) => Roots::Linear(-c / b),
+            (true, false) => Roots::None,
+            (true, true) => Roots::Infinite,
+        };
+    }
+    let discriminant = b * b - T::from(4) * a * c;
+    if discriminant < zero {
+        return Roots::Complex {
+            re: -b / (two * a),
+            im: (-discriminant).sqrt() / (two * a).abs(),
+        };
+    }
+    if discriminant == zero {
+        return Roots::Repeated(-b / (two * a));
+    }
+    // the usual (-b ± sqrt(d)) / 2a takes two nearly equal numbers away from each other for one
+    // of the roots when b is much bigger than ac, losing most of its precision. working out the
+    // other root first, where they are added, and then using x1 * x2 = c / a avoids that
+    let q = -(b + discriminant.sqrt().copysign(b)) / two;
+    let (x1, x2) = (q / a, c / q);
+    match x1 < x2 {
+        true => Roots::Two(x1, x2),
+        false => Roots::Two(x2, x1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_real_roots() {
+        assert_eq!(Roots::Two(10.0, 20.0), solve_quadratic(-1.0, 30.0, -200.0));
+        assert_eq!(Roots::Two(-3.0, 2.0), solve_quadratic(1.0, 1.0, -6.0));
+        assert_eq!(Roots::Repeated(3.0), solve_quadratic(1.0, -6.0, 9.0));
+        assert_eq!(Roots::Two(0.0, 4.0), solve_quadratic(1.0, -4.0, 0.0));
+        assert_eq!(vec![10.0, 20.0], solve_quadratic(-1.0, 30.0, -200.0).real());
+
+        // the small root is about -1e-8, which the textbook formula gets badly wrong
+        let Roots::Two(big, small) = solve_quadratic(1.0, 1e8, 1.0) else {
+            panic!("expected two roots");
+        };
+        assert!((small + 1e-8).abs() < 1e-20);
+        assert!((big + 1e8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_other_roots() {
+        assert_eq!(
+            Roots::Complex { re: 1.0, im: 2.0 },
+            solve_quadratic(1.0, -2.0, 5.0)
+        );
+        assert_eq!(
+            Roots::Complex { re: 1.0, im: 2.0 },
+            solve_quadratic(-1.0, 2.0, -5.0)
+        );
+        assert_eq!(Roots::Linear(2.0), solve_quadratic(0.0, 3.0, -6.0));
+        assert_eq!(Roots::None, solve_quadratic(0.0, 0.0, 1.0));
+        assert_eq!(Roots::Infinite, solve_quadratic(0.0, 0.0, 0.0));
+        assert_eq!(Roots::None, solve_quadratic(f64::NAN, 1.0, 1.0));
+        assert!(solve_quadratic(1.0, -2.0, 5.0).real().is_empty());
+    }
+
+    #[test]
+    fn test_f32() {
+        assert_eq!(
+            Roots::Two(10.0f32, 20.0),
+            solve_quadratic(-1.0f32, 30.0, -200.0)
+        );
+        assert_eq!(Roots::Repeated(-1.0f32), solve_quadratic(1.0f32, 2.0, 1.0));
+    }
+}
@@ -1,10 +1,30 @@
-use std::collections::BTreeSet;
+use std::process::ExitCode;
 
-use day_07::{calculate_total_winnings, rank_hands, Hand};
+use aoc_common::{open_from_args, Solution};
+use day_07::{rank, Day07, Hand, Ranking, Rules, TiePolicy};
 
-fn main() {
-    // automatically sorts the hands using Ord implementation in ascending order
-    let hands: BTreeSet<Hand> = rank_hands("input.txt");
-    let total_winnings = calculate_total_winnings(&hands);
-    println!("Total winnings: {total_winnings}");
+fn main() -> ExitCode {
+    let hands: Vec<Hand> = match open_from_args(env!("CARGO_MANIFEST_DIR"))
+        .map_err(day_07::Error::from)
+        .and_then(Day07::parse)
+    {
+        Ok(hands) => hands,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let ranking: Ranking = match rank(&hands, Rules::Standard, TiePolicy::default()) {
+        Ok(ranking) => ranking,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    // tied hands share a rank, so list them in case that isn't what was wanted
+    for tie in &ranking.ties {
+        println!("Tied: {tie}");
+    }
+    println!("Total winnings: {}", ranking.total_winnings());
+    ExitCode::SUCCESS
 }
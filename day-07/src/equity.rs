@@ -0,0 +1,360 @@
+use std::{cmp::Ordering, collections::BTreeMap, fmt};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{Card, Error, Hand, HandType, Rules, HAND_SIZE};
+
+// the cards that hands are dealt from, which can have any number of each card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    pub fn new(cards: Vec<Card>) -> Self {
+        Self { cards }
+    }
+
+    // four of each card, like a deck of playing cards
+    pub fn standard() -> Self {
+        Self::with_copies(4)
+    }
+
+    pub fn with_copies(copies: usize) -> Self {
+        Self::new(
+            Card::ALL
+                .iter()
+                .flat_map(|card| [*card].repeat(copies))
+                .collect(),
+        )
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    // the deck with the cards taken out, which have to be in it
+    pub fn without(&self, cards: &[Card]) -> Result<Deck, Error> {
+        let mut left = self.cards.clone();
+        for card in cards {
+            let i = left
+                .iter()
+                .position(|c| c == card)
+                .ok_or(Error::NotInDeck(*card))?;
+            left.swap_remove(i);
+        }
+        Ok(Deck::new(left))
+    }
+}
+
+// how the missing cards are dealt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    // every way the missing cards could be dealt. the number of deals goes up by about the number
+    // of different cards in the deck for each missing card, so this is only quick with a few
+    Exhaustive,
+    // random deals, which are the same every time for the same seed
+    Sample { trials: u64, seed: u64 },
+}
+
+// how often a hand beats another once both are dealt the rest of their cards, counted in deals
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Equity {
+    pub wins: u64,
+    pub ties: u64,
+    pub losses: u64,
+    // how many deals left each hand with each type
+    pub hand_types: BTreeMap<HandType, u64>,
+    pub opponent_types: BTreeMap<HandType, u64>,
+}
+
+impl Equity {
+    pub fn deals(&self) -> u64 {
+        self.wins + self.ties + self.losses
+    }
+
+    // the fraction of the deals that count is, which is 0 if nothing was dealt
+    pub fn probability(&self, count: u64) -> f64 {
+        match self.deals() {
+            0 => 0.0,
+            deals => count as f64 / deals as f64,
+        }
+    }
+
+    pub fn win(&self) -> f64 {
+        self.probability(self.wins)
+    }
+
+    pub fn tie(&self) -> f64 {
+        self.probability(self.ties)
+    }
+
+    pub fn loss(&self) -> f64 {
+        self.probability(self.losses)
+    }
+
+    fn record(&mut self, hand: &Hand, opponent: &Hand, deals: u64) {
+        match hand.strength_cmp(opponent) {
+            Ordering::Greater => self.wins += deals,
+            Ordering::Equal => self.ties += deals,
+            Ordering::Less => self.losses += deals,
+        }
+        *self.hand_types.entry(hand.hand_type.clone()).or_default() += deals;
+        *self
+            .opponent_types
+            .entry(opponent.hand_type.clone())
+            .or_default() += deals;
+    }
+}
+
+impl fmt::Display for Equity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "win {:.2}%, tie {:.2}%, loss {:.2}% over {} deals",
+            self.win() * 100.0,
+            self.tie() * 100.0,
+            self.loss() * 100.0,
+            self.deals()
+        )
+    }
+}
+
+// how likely hand is to beat opponent once they have both been dealt the rest of their cards
+// from what is left of the deck. the cards dealt go after the known ones, so the known cards are
+// the first cards of each hand when they are compared
+pub fn equity(
+    hand: &[Card],
+    opponent: &[Card],
+    deck: &Deck,
+    rules: Rules,
+    method: Method,
+) -> Result<Equity, Error> {
+    for known in [hand, opponent] {
+        if known.len() > HAND_SIZE {
+            return Err(Error::InvalidHandSize(known.len()));
+        }
+    }
+    let left = deck.without(&[hand, opponent].concat())?;
+    let needed = 2 * HAND_SIZE - hand.len() - opponent.len();
+    if left.cards.len() < needed {
+        return Err(Error::NotEnoughCards {
+            needed,
+            left: left.cards.len(),
+        });
+    }
+
+    let mut equity = Equity::default();
+    let mut play = |dealt: &[Card], deals: u64| -> Result<(), Error> {
+        let (to_hand, to_opponent) = dealt.split_at(HAND_SIZE - hand.len());
+        let hand = Hand::new([hand, to_hand].concat(), 0, rules)?;
+        let opponent = Hand::new([opponent, to_opponent].concat(), 0, rules)?;
+        equity.record(&hand, &opponent, deals);
+        Ok(())
+    };
+    match method {
+        Method::Exhaustive => {
+            // every deal is counted once, so this is how many there are in total
+            (0..needed)
+                .try_fold(1u64, |total, i| {
+                    total.checked_mul((left.cards.len() - i) as u64)
+                })
+                .ok_or(Error::TooManyDeals)?;
+            let mut counts: Vec<(Card, u64)> = Card::ALL
+                .iter()
+                .map(|card| {
+                    (
+                        *card,
+                        left.cards.iter().filter(|c| *c == card).count() as u64,
+                    )
+                })
+                .filter(|(_, count)| *count > 0)
+                .collect();
+            deal_all(&mut counts, needed, &mut vec![], 1, &mut play)?;
+        }
+        Method::Sample { trials, seed } => {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let mut cards = left.cards;
+            for _ in 0..trials {
+                let (dealt, _) = cards.partial_shuffle(&mut rng, needed);
+                play(dealt, 1)?;
+            }
+        }
+    }
+    Ok(equity)
+}
+
+// goes through every different order the needed cards can come out in. cards there are more
+// copies of come out more often, so each one counts for the number of deals that give it
+fn deal_all<F>(
+    counts: &mut [(Card, u64)],
+    needed: usize,
+    dealt: &mut Vec<Card>,
+    deals: u64,
+    play: &mut F,
+) -> Result<(), Error>
+where
+    F: FnMut(&[Card], u64) -> Result<(), Error>,
+{
+    if dealt.len() == needed {
+        return play(dealt, deals);
+    }
+    for i in 0..counts.len() {
+        let (card, count) = counts[i];
+        if count == 0 {
+            continue;
+        }
+        counts[i].1 -= 1;
+        dealt.push(card);
+        let deals = deals.checked_mul(count).ok_or(Error::TooManyDeals)?;
+        deal_all(counts, needed, dealt, deals, play)?;
+        dealt.pop();
+        counts[i].1 += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(input: &str) -> Vec<Card> {
+        input.chars().map(|c| Card::parse(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_deck() {
+        assert_eq!(52, Deck::standard().cards().len());
+        let deck = Deck::new(cards("AAK"));
+        assert_eq!(Ok(Deck::new(cards("K"))), deck.without(&cards("AA")));
+        assert_eq!(Err(Error::NotInDeck(Card::A)), deck.without(&cards("AAA")));
+    }
+
+    #[test]
+    fn test_exhaustive() {
+        let equity = |hand: &str, opponent: &str, deck: &Deck, rules: Rules| {
+            super::equity(
+                &cards(hand),
+                &cards(opponent),
+                deck,
+                rules,
+                Method::Exhaustive,
+            )
+            .unwrap()
+        };
+
+        // nothing left to deal. both are two pair, and the second K beats the T
+        let full = equity("KK677", "KTJJT", &Deck::standard(), Rules::Standard);
+        assert_eq!((1, 0, 0), (full.wins, full.ties, full.losses));
+        assert_eq!(1.0, full.win());
+
+        // the KKKK only wins if it gets one of the 4 Ks left out of 95 cards
+        let five_kings = equity("KKKK", "AAAAQ", &Deck::with_copies(8), Rules::Standard);
+        assert_eq!(
+            (4, 0, 91),
+            (five_kings.wins, five_kings.ties, five_kings.losses)
+        );
+        assert_eq!(Some(&4), five_kings.hand_types.get(&HandType::FiveOfAKind));
+        assert_eq!(Some(&91), five_kings.hand_types.get(&HandType::FourOfAKind));
+        assert_eq!(
+            Some(&95),
+            five_kings.opponent_types.get(&HandType::FourOfAKind)
+        );
+
+        // all the Js are in the hand, so it is always four of a kind and loses to the As unless
+        // they are jokers
+        let jacks = equity("JJJJ", "AAAAK", &Deck::standard(), Rules::Standard);
+        assert_eq!((0, 0, 43), (jacks.wins, jacks.ties, jacks.losses));
+        let jokers = equity("JJJJ", "AAAAK", &Deck::standard(), Rules::Jokers);
+        assert_eq!((43, 0, 0), (jokers.wins, jokers.ties, jokers.losses));
+
+        // each of the 44 * 43 ordered deals of the last two cards counts once
+        let both = equity("2345", "2345", &Deck::standard(), Rules::Standard);
+        assert_eq!(44 * 43, both.deals());
+        assert_eq!(both.wins, both.losses);
+        assert!((both.win() + both.tie() + both.loss() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sample() {
+        let sample = |seed: u64| {
+            equity(
+                &cards("KKKK"),
+                &cards("AAAAQ"),
+                &Deck::with_copies(8),
+                Rules::Standard,
+                Method::Sample {
+                    trials: 20_000,
+                    seed,
+                },
+            )
+            .unwrap()
+        };
+        let first = sample(7);
+        assert_eq!(20_000, first.deals());
+        assert_eq!(first, sample(7));
+        assert!((first.win() - 4.0 / 95.0).abs() < 0.01, "{first}");
+
+        let no_trials = equity(
+            &[],
+            &[],
+            &Deck::standard(),
+            Rules::Standard,
+            Method::Sample { trials: 0, seed: 7 },
+        )
+        .unwrap();
+        assert_eq!(0.0, no_trials.win());
+    }
+
+    #[test]
+    fn test_equity_errors() {
+        let deck = Deck::standard();
+        assert_eq!(
+            Err(Error::InvalidHandSize(6)),
+            equity(
+                &cards("AKQJT9"),
+                &[],
+                &deck,
+                Rules::Standard,
+                Method::Exhaustive
+            )
+        );
+        assert_eq!(
+            Err(Error::NotInDeck(Card::A)),
+            equity(
+                &cards("AAA"),
+                &cards("AA"),
+                &deck,
+                Rules::Standard,
+                Method::Exhaustive
+            )
+        );
+        assert_eq!(
+            Err(Error::NotEnoughCards { needed: 2, left: 1 }),
+            equity(
+                &cards("AAAA"),
+                &cards("KKKK"),
+                &Deck::new(cards("AAAAKKKKQ")),
+                Rules::Standard,
+                Method::Exhaustive
+            )
+        );
+        // about 1.5e20 ways to deal 10 cards from 104
+        assert_eq!(
+            Err(Error::TooManyDeals),
+            equity(
+                &[],
+                &[],
+                &Deck::with_copies(8),
+                Rules::Standard,
+                Method::Exhaustive
+            )
+        );
+        let mut counts = [(Card::A, u64::MAX), (Card::K, 2)];
+        assert_eq!(
+            Err(Error::TooManyDeals),
+            deal_all(&mut counts, 2, &mut vec![], 1, &mut |_, _| Ok(()))
+        );
+    }
+}
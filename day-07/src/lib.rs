@@ -1,13 +1,113 @@
-use std::{
-    cmp::Ordering,
-    collections::{BTreeSet, HashMap},
-    fs::File,
-    io::{self, BufRead},
-    iter::zip,
-    path::Path,
-};
-
-#[derive(Debug, PartialEq, Eq)]
+use std::{cmp::Ordering, collections::HashMap, fmt, io::BufRead, iter::zip};
+
+use aoc_common::{read_lines, InputError, Solution, Span};
+
+mod equity;
+mod poker;
+
+pub use equity::{equity, Deck, Equity, Method};
+pub use poker::{evaluate, parse_cards, Category, PokerCard, PokerHand, Suit};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u32 = 7;
+
+    type Input = Vec<Hand>;
+    type Answer = u64;
+    type Error = Error;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, Self::Error> {
+        parse_hands(reader)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        Ok(rank(input, Rules::Standard, TiePolicy::default())?.total_winnings())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        Ok(rank(input, Rules::Jokers, TiePolicy::default())?.total_winnings())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    Input(InputError),
+    InvalidCard(Span),
+    InvalidBid(Span),
+    // the line has cards but nothing after them
+    MissingBid(Span),
+    WrongHandSize(Span),
+    // a list of cards that was never parsed from the input was the wrong size, which can only
+    // happen if it is empty
+    InvalidHandSize(usize),
+    // ranking found hands that can't be told apart with TiePolicy::Error
+    TiedHands(Tie),
+    // the same card twice in a list of poker cards
+    DuplicateCard(Span),
+    // the known cards use more of a card than the deck has
+    NotInDeck(Card),
+    // there aren't enough cards left in the deck to finish the hands
+    NotEnoughCards { needed: usize, left: usize },
+    // dealing every way would count more deals than fit in a u64
+    TooManyDeals,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(e) => write!(f, "{e}"),
+            Self::InvalidCard(span) => write!(f, "invalid card {span}"),
+            Self::InvalidBid(span) => write!(f, "invalid bid {span}"),
+            Self::MissingBid(span) => write!(f, "missing bid for hand {span}"),
+            Self::WrongHandSize(span) => write!(f, "wrong number of cards in hand {span}"),
+            Self::InvalidHandSize(size) => write!(f, "a hand can't have {size} cards"),
+            Self::TiedHands(tie) => write!(f, "tied hands: {tie}"),
+            Self::DuplicateCard(span) => write!(f, "duplicate card {span}"),
+            Self::NotInDeck(card) => write!(f, "there aren't enough {card}s in the deck"),
+            Self::NotEnoughCards { needed, left } => write!(
+                f,
+                "{needed} cards are needed to finish the hands but only {left} are left"
+            ),
+            Self::TooManyDeals => write!(f, "too many deals to try them all, use sampling"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Input(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::InvalidCard(span) => Self::InvalidCard(span.on_line(line)),
+            Self::InvalidBid(span) => Self::InvalidBid(span.on_line(line)),
+            Self::MissingBid(span) => Self::MissingBid(span.on_line(line)),
+            Self::WrongHandSize(span) => Self::WrongHandSize(span.on_line(line)),
+            Self::DuplicateCard(span) => Self::DuplicateCard(span.on_line(line)),
+            e => e,
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Self::Input(e)
+    }
+}
+
+// the number of cards the puzzle's hands have
+pub const HAND_SIZE: usize = 5;
+
+// the named types are for five card hands. any other hand size gets a type from the number of
+// each card it has, from most to least, e.g. [3, 3] for a six card hand with two threes of a kind
+#[derive(Debug, Clone)]
 pub enum HandType {
     FiveOfAKind,
     FourOfAKind,
@@ -16,50 +116,43 @@ pub enum HandType {
     TwoPair,
     OnePair,
     HighCard,
+    Counts(Vec<u32>),
 }
 
 impl HandType {
-    fn get_value(&self) -> u32 {
+    // how many of each card the type has, from most to least
+    pub fn signature(&self) -> Vec<u32> {
         match self {
-            Self::FiveOfAKind => 6,
-            Self::FourOfAKind => 5,
-            Self::FullHouse => 4,
-            Self::ThreeOfAKind => 3,
-            Self::TwoPair => 2,
-            Self::OnePair => 1,
-            Self::HighCard => 0,
+            Self::FiveOfAKind => vec![5],
+            Self::FourOfAKind => vec![4, 1],
+            Self::FullHouse => vec![3, 2],
+            Self::ThreeOfAKind => vec![3, 1, 1],
+            Self::TwoPair => vec![2, 2, 1],
+            Self::OnePair => vec![2, 1, 1, 1],
+            Self::HighCard => vec![1, 1, 1, 1, 1],
+            Self::Counts(counts) => counts.clone(),
         }
     }
 
-    pub fn get_hand_type(cards: &Vec<Card>) -> Result<Self, &'static str> {
-        if cards.len() != 5 {
-            return Err("Hand contained invalid number of cards");
+    // the type with the given counts, which should be sorted from most to least
+    pub fn from_signature(counts: Vec<u32>) -> Self {
+        match counts.as_slice() {
+            [5] => Self::FiveOfAKind,
+            [4, 1] => Self::FourOfAKind,
+            [3, 2] => Self::FullHouse,
+            [3, 1, 1] => Self::ThreeOfAKind,
+            [2, 2, 1] => Self::TwoPair,
+            [2, 1, 1, 1] => Self::OnePair,
+            [1, 1, 1, 1, 1] => Self::HighCard,
+            _ => Self::Counts(counts),
         }
-        let counter = Self::count_cards(cards);
-        let hand_type = match counter.values().max().unwrap() {
-            5 => Self::FiveOfAKind,
-            4 => Self::FourOfAKind,
-            3 => {
-                if counter.len() == 2 {
-                    Self::FullHouse
-                } else {
-                    Self::ThreeOfAKind
-                }
-            }
-            2 => {
-                if counter.len() == 3 {
-                    Self::TwoPair
-                } else {
-                    Self::OnePair
-                }
-            }
-            1 => Self::HighCard,
-            _ => return Err("Something went wrong counting the numbers of cards"),
-        };
-        Ok(hand_type)
     }
 
-    pub fn count_cards(cards: &Vec<Card>) -> HashMap<Card, u32> {
+    pub fn get_hand_type(cards: &[Card]) -> Result<Self, Error> {
+        Self::with_wildcards(cards, Wildcards::default())
+    }
+
+    pub fn count_cards(cards: &[Card]) -> HashMap<Card, u32> {
         let mut counter: HashMap<Card, u32> = HashMap::new();
         for c in cards {
             counter.entry(*c).and_modify(|x| *x += 1).or_insert(1);
@@ -67,132 +160,67 @@ impl HandType {
         counter
     }
 
-    pub fn apply_joker_rule(cards: &Vec<Card>) -> Result<Self, &'static str> {
-        let mut counter: HashMap<Card, u32> = HashMap::new();
-        let mut jokers: u32 = 0;
-        for card in cards {
-            if card == &Card::J {
-                jokers += 1;
-            } else {
-                counter.entry(*card).and_modify(|x| *x += 1).or_insert(1);
-            }
-        }
+    pub fn apply_joker_rule(cards: &[Card]) -> Result<Self, Error> {
+        Self::with_wildcards(cards, Wildcards::new(&[Card::J]))
+    }
 
-        // i hate this and myself
-        match jokers {
-            0 => Self::get_hand_type(cards),
-            1 => {
-                if counter.len() == 4 {
-                    // all four cards are different e.g JAQT6
-                    Ok(Self::OnePair)
-                } else if counter.len() == 3 {
-                    // three different cards e.g 7JK73
-                    Ok(Self::ThreeOfAKind)
-                } else if counter.len() == 2 {
-                    // two different cards e.g 99J55 or 999J5
-                    match counter.iter().map(|(_, y)| y).max_by(|x, y| x.cmp(y)) {
-                        Some(n) => {
-                            if *n == 3 {
-                                return Ok(Self::FourOfAKind);
-                            } else if *n == 2 {
-                                return Ok(Self::FullHouse);
-                            } else {
-                                return Err("I have no idea how this could happen");
-                            }
-                        }
-                        None => Err("What the heck"),
-                    }
-                } else {
-                    // all four cards are the same e.g AAAAJ
-                    Ok(Self::FiveOfAKind)
-                }
-            }
-            2 => {
-                if counter.len() == 3 {
-                    // three different cards e.g JJAQK
-                    Ok(Self::ThreeOfAKind)
-                } else if counter.len() == 2 {
-                    // two different cards e.g JJKK8
-                    Ok(Self::FourOfAKind)
-                } else {
-                    // other three cards are three of a kind
-                    Ok(Self::FiveOfAKind)
-                }
-            }
-            3 => {
-                if counter.len() == 2 {
-                    // two different cards
-                    Ok(Self::FourOfAKind)
-                } else {
-                    Ok(Self::FiveOfAKind)
-                }
-            }
-            4 | 5 => Ok(Self::FiveOfAKind),
-            _ => Err("wtf"),
+    // the best type the hand can make when the wildcards can stand in for any card. adding them
+    // all to whichever card there is most of always does best, since the types are compared by
+    // their biggest count first
+    pub fn with_wildcards(cards: &[Card], wildcards: Wildcards) -> Result<Self, Error> {
+        if cards.is_empty() {
+            return Err(Error::InvalidHandSize(0));
+        }
+        let (wild, natural): (Vec<Card>, Vec<Card>) =
+            cards.iter().partition(|c| wildcards.contains(**c));
+        let mut counts: Vec<u32> = Self::count_cards(&natural).into_values().collect();
+        counts.sort_by(|a, b| b.cmp(a));
+        match counts.first_mut() {
+            Some(most) => *most += wild.len() as u32,
+            None => counts.push(wild.len() as u32),
         }
+        Ok(Self::from_signature(counts))
+    }
+}
+
+// types are the same if their counts are, so Counts(vec![5]) is FiveOfAKind
+impl PartialEq for HandType {
+    fn eq(&self, other: &Self) -> bool {
+        self.signature() == other.signature()
     }
 }
 
+impl Eq for HandType {}
+
 impl PartialOrd for HandType {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.get_value().cmp(&other.get_value()))
+        Some(self.cmp(other))
     }
 }
 
-// there has to be a better way
+// comparing the counts from the most down gives the puzzle's order for five cards, and carries
+// on making sense for other sizes
 impl Ord for HandType {
     fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (HandType::FiveOfAKind, HandType::FiveOfAKind) => Ordering::Equal,
-            (HandType::FiveOfAKind, HandType::FourOfAKind) => Ordering::Greater,
-            (HandType::FiveOfAKind, HandType::FullHouse) => Ordering::Greater,
-            (HandType::FiveOfAKind, HandType::ThreeOfAKind) => Ordering::Greater,
-            (HandType::FiveOfAKind, HandType::TwoPair) => Ordering::Greater,
-            (HandType::FiveOfAKind, HandType::OnePair) => Ordering::Greater,
-            (HandType::FiveOfAKind, HandType::HighCard) => Ordering::Greater,
-            (HandType::FourOfAKind, HandType::FiveOfAKind) => Ordering::Less,
-            (HandType::FourOfAKind, HandType::FourOfAKind) => Ordering::Equal,
-            (HandType::FourOfAKind, HandType::FullHouse) => Ordering::Greater,
-            (HandType::FourOfAKind, HandType::ThreeOfAKind) => Ordering::Greater,
-            (HandType::FourOfAKind, HandType::TwoPair) => Ordering::Greater,
-            (HandType::FourOfAKind, HandType::OnePair) => Ordering::Greater,
-            (HandType::FourOfAKind, HandType::HighCard) => Ordering::Greater,
-            (HandType::FullHouse, HandType::FiveOfAKind) => Ordering::Less,
-            (HandType::FullHouse, HandType::FourOfAKind) => Ordering::Less,
-            (HandType::FullHouse, HandType::FullHouse) => Ordering::Equal,
-            (HandType::FullHouse, HandType::ThreeOfAKind) => Ordering::Greater,
-            (HandType::FullHouse, HandType::TwoPair) => Ordering::Greater,
-            (HandType::FullHouse, HandType::OnePair) => Ordering::Greater,
-            (HandType::FullHouse, HandType::HighCard) => Ordering::Greater,
-            (HandType::ThreeOfAKind, HandType::FiveOfAKind) => Ordering::Less,
-            (HandType::ThreeOfAKind, HandType::FourOfAKind) => Ordering::Less,
-            (HandType::ThreeOfAKind, HandType::FullHouse) => Ordering::Less,
-            (HandType::ThreeOfAKind, HandType::ThreeOfAKind) => Ordering::Equal,
-            (HandType::ThreeOfAKind, HandType::TwoPair) => Ordering::Greater,
-            (HandType::ThreeOfAKind, HandType::OnePair) => Ordering::Greater,
-            (HandType::ThreeOfAKind, HandType::HighCard) => Ordering::Greater,
-            (HandType::TwoPair, HandType::FiveOfAKind) => Ordering::Less,
-            (HandType::TwoPair, HandType::FourOfAKind) => Ordering::Less,
-            (HandType::TwoPair, HandType::FullHouse) => Ordering::Less,
-            (HandType::TwoPair, HandType::ThreeOfAKind) => Ordering::Less,
-            (HandType::TwoPair, HandType::TwoPair) => Ordering::Equal,
-            (HandType::TwoPair, HandType::OnePair) => Ordering::Greater,
-            (HandType::TwoPair, HandType::HighCard) => Ordering::Greater,
-            (HandType::OnePair, HandType::FiveOfAKind) => Ordering::Less,
-            (HandType::OnePair, HandType::FourOfAKind) => Ordering::Less,
-            (HandType::OnePair, HandType::FullHouse) => Ordering::Less,
-            (HandType::OnePair, HandType::ThreeOfAKind) => Ordering::Less,
-            (HandType::OnePair, HandType::TwoPair) => Ordering::Less,
-            (HandType::OnePair, HandType::OnePair) => Ordering::Equal,
-            (HandType::OnePair, HandType::HighCard) => Ordering::Greater,
-            (HandType::HighCard, HandType::FiveOfAKind) => Ordering::Less,
-            (HandType::HighCard, HandType::FourOfAKind) => Ordering::Less,
-            (HandType::HighCard, HandType::FullHouse) => Ordering::Less,
-            (HandType::HighCard, HandType::ThreeOfAKind) => Ordering::Less,
-            (HandType::HighCard, HandType::TwoPair) => Ordering::Less,
-            (HandType::HighCard, HandType::OnePair) => Ordering::Less,
-            (HandType::HighCard, HandType::HighCard) => Ordering::Equal,
-        }
+        self.signature().cmp(&other.signature())
+    }
+}
+
+// a set of cards that can stand in for any other card
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub struct Wildcards(u16);
+
+impl Wildcards {
+    pub fn new(cards: &[Card]) -> Self {
+        Self(cards.iter().fold(0, |set, c| set | (1 << c.get_value())))
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & (1 << card.get_value()) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
     }
 }
 
@@ -214,8 +242,25 @@ pub enum Card {
 }
 
 impl Card {
-    // for part 1
-    /*     pub fn get_value(&self) -> u32 {
+    // every card, from weakest to strongest under the standard rules
+    pub const ALL: [Card; 13] = [
+        Card::Two,
+        Card::Three,
+        Card::Four,
+        Card::Five,
+        Card::Six,
+        Card::Seven,
+        Card::Eight,
+        Card::Nine,
+        Card::T,
+        Card::J,
+        Card::Q,
+        Card::K,
+        Card::A,
+    ];
+
+    // the value of the card under the standard rules, where J is a jack
+    pub fn get_value(&self) -> u32 {
         match self {
             Card::A => 14,
             Card::K => 13,
@@ -231,307 +276,166 @@ impl Card {
             Card::Three => 3,
             Card::Two => 2,
         }
-    } */
+    }
 
-    pub fn get_value(&self) -> u32 {
+    pub fn symbol(&self) -> char {
         match self {
-            Card::A => 13,
-            Card::K => 12,
-            Card::Q => 11,
-            Card::T => 10,
-            Card::Nine => 9,
-            Card::Eight => 8,
-            Card::Seven => 7,
-            Card::Six => 6,
-            Card::Five => 5,
-            Card::Four => 4,
-            Card::Three => 3,
-            Card::Two => 2,
-            Card::J => 0,
+            Card::A => 'A',
+            Card::K => 'K',
+            Card::Q => 'Q',
+            Card::J => 'J',
+            Card::T => 'T',
+            Card::Nine => '9',
+            Card::Eight => '8',
+            Card::Seven => '7',
+            Card::Six => '6',
+            Card::Five => '5',
+            Card::Four => '4',
+            Card::Three => '3',
+            Card::Two => '2',
         }
     }
-    pub fn parse(c: char) -> Result<Card, &'static str> {
+
+    pub fn parse(c: char) -> Option<Card> {
         match c {
-            'A' => Ok(Card::A),
-            'K' => Ok(Card::K),
-            'Q' => Ok(Card::Q),
-            'J' => Ok(Card::J),
-            'T' => Ok(Card::T),
-            '9' => Ok(Card::Nine),
-            '8' => Ok(Card::Eight),
-            '7' => Ok(Card::Seven),
-            '6' => Ok(Card::Six),
-            '5' => Ok(Card::Five),
-            '4' => Ok(Card::Four),
-            '3' => Ok(Card::Three),
-            '2' => Ok(Card::Two),
-            _ => Err("Found invalid character"),
+            'A' => Some(Card::A),
+            'K' => Some(Card::K),
+            'Q' => Some(Card::Q),
+            'J' => Some(Card::J),
+            'T' => Some(Card::T),
+            '9' => Some(Card::Nine),
+            '8' => Some(Card::Eight),
+            '7' => Some(Card::Seven),
+            '6' => Some(Card::Six),
+            '5' => Some(Card::Five),
+            '4' => Some(Card::Four),
+            '3' => Some(Card::Three),
+            '2' => Some(Card::Two),
+            _ => None,
         }
     }
 }
 
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.get_value().cmp(&other.get_value()))
+        Some(self.cmp(other))
     }
 }
 
-// why
+// the standard order. use Rules::card_value to compare cards with jokers
 impl Ord for Card {
     fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (Card::A, Card::A) => Ordering::Equal,
-            (Card::A, Card::K) => Ordering::Greater,
-            (Card::A, Card::Q) => Ordering::Greater,
-            (Card::A, Card::J) => Ordering::Greater,
-            (Card::A, Card::T) => Ordering::Greater,
-            (Card::A, Card::Nine) => Ordering::Greater,
-            (Card::A, Card::Eight) => Ordering::Greater,
-            (Card::A, Card::Seven) => Ordering::Greater,
-            (Card::A, Card::Six) => Ordering::Greater,
-            (Card::A, Card::Five) => Ordering::Greater,
-            (Card::A, Card::Four) => Ordering::Greater,
-            (Card::A, Card::Three) => Ordering::Greater,
-            (Card::A, Card::Two) => Ordering::Greater,
-            (Card::K, Card::A) => Ordering::Less,
-            (Card::K, Card::K) => Ordering::Equal,
-            (Card::K, Card::Q) => Ordering::Greater,
-            (Card::K, Card::J) => Ordering::Greater,
-            (Card::K, Card::T) => Ordering::Greater,
-            (Card::K, Card::Nine) => Ordering::Greater,
-            (Card::K, Card::Eight) => Ordering::Greater,
-            (Card::K, Card::Seven) => Ordering::Greater,
-            (Card::K, Card::Six) => Ordering::Greater,
-            (Card::K, Card::Five) => Ordering::Greater,
-            (Card::K, Card::Four) => Ordering::Greater,
-            (Card::K, Card::Three) => Ordering::Greater,
-            (Card::K, Card::Two) => Ordering::Greater,
-            (Card::Q, Card::A) => Ordering::Less,
-            (Card::Q, Card::K) => Ordering::Less,
-            (Card::Q, Card::Q) => Ordering::Equal,
-            (Card::Q, Card::J) => Ordering::Greater,
-            (Card::Q, Card::T) => Ordering::Greater,
-            (Card::Q, Card::Nine) => Ordering::Greater,
-            (Card::Q, Card::Eight) => Ordering::Greater,
-            (Card::Q, Card::Seven) => Ordering::Greater,
-            (Card::Q, Card::Six) => Ordering::Greater,
-            (Card::Q, Card::Five) => Ordering::Greater,
-            (Card::Q, Card::Four) => Ordering::Greater,
-            (Card::Q, Card::Three) => Ordering::Greater,
-            (Card::Q, Card::Two) => Ordering::Greater,
-            (Card::J, Card::A) => Ordering::Less,
-            (Card::J, Card::K) => Ordering::Less,
-            (Card::J, Card::Q) => Ordering::Less,
-            (Card::J, Card::J) => Ordering::Equal,
-            /*             (Card::J, Card::T) => Ordering::Greater,
-            (Card::J, Card::Nine) => Ordering::Greater,
-            (Card::J, Card::Eight) => Ordering::Greater,
-            (Card::J, Card::Seven) => Ordering::Greater,
-            (Card::J, Card::Six) => Ordering::Greater,
-            (Card::J, Card::Five) => Ordering::Greater,
-            (Card::J, Card::Four) => Ordering::Greater,
-            (Card::J, Card::Three) => Ordering::Greater,
-            (Card::J, Card::Two) => Ordering::Greater, */
-            (Card::J, Card::T) => Ordering::Less,
-            (Card::J, Card::Nine) => Ordering::Less,
-            (Card::J, Card::Eight) => Ordering::Less,
-            (Card::J, Card::Seven) => Ordering::Less,
-            (Card::J, Card::Six) => Ordering::Less,
-            (Card::J, Card::Five) => Ordering::Less,
-            (Card::J, Card::Four) => Ordering::Less,
-            (Card::J, Card::Three) => Ordering::Less,
-            (Card::J, Card::Two) => Ordering::Less,
-            (Card::T, Card::A) => Ordering::Less,
-            (Card::T, Card::K) => Ordering::Less,
-            (Card::T, Card::Q) => Ordering::Less,
-            //(Card::T, Card::J) => Ordering::Less,
-            (Card::T, Card::J) => Ordering::Greater,
-            (Card::T, Card::T) => Ordering::Equal,
-            (Card::T, Card::Nine) => Ordering::Greater,
-            (Card::T, Card::Eight) => Ordering::Greater,
-            (Card::T, Card::Seven) => Ordering::Greater,
-            (Card::T, Card::Six) => Ordering::Greater,
-            (Card::T, Card::Five) => Ordering::Greater,
-            (Card::T, Card::Four) => Ordering::Greater,
-            (Card::T, Card::Three) => Ordering::Greater,
-            (Card::T, Card::Two) => Ordering::Greater,
-            (Card::Nine, Card::A) => Ordering::Less,
-            (Card::Nine, Card::K) => Ordering::Less,
-            (Card::Nine, Card::Q) => Ordering::Less,
-            //(Card::Nine, Card::J) => Ordering::Less,
-            (Card::Nine, Card::J) => Ordering::Greater,
-            (Card::Nine, Card::T) => Ordering::Less,
-            (Card::Nine, Card::Nine) => Ordering::Equal,
-            (Card::Nine, Card::Eight) => Ordering::Greater,
-            (Card::Nine, Card::Seven) => Ordering::Greater,
-            (Card::Nine, Card::Six) => Ordering::Greater,
-            (Card::Nine, Card::Five) => Ordering::Greater,
-            (Card::Nine, Card::Four) => Ordering::Greater,
-            (Card::Nine, Card::Three) => Ordering::Greater,
-            (Card::Nine, Card::Two) => Ordering::Greater,
-            (Card::Eight, Card::A) => Ordering::Less,
-            (Card::Eight, Card::K) => Ordering::Less,
-            (Card::Eight, Card::Q) => Ordering::Less,
-            //(Card::Eight, Card::J) => Ordering::Less,
-            (Card::Eight, Card::J) => Ordering::Greater,
-            (Card::Eight, Card::T) => Ordering::Less,
-            (Card::Eight, Card::Nine) => Ordering::Less,
-            (Card::Eight, Card::Eight) => Ordering::Equal,
-            (Card::Eight, Card::Seven) => Ordering::Greater,
-            (Card::Eight, Card::Six) => Ordering::Greater,
-            (Card::Eight, Card::Five) => Ordering::Greater,
-            (Card::Eight, Card::Four) => Ordering::Greater,
-            (Card::Eight, Card::Three) => Ordering::Greater,
-            (Card::Eight, Card::Two) => Ordering::Greater,
-            (Card::Seven, Card::A) => Ordering::Less,
-            (Card::Seven, Card::K) => Ordering::Less,
-            (Card::Seven, Card::Q) => Ordering::Less,
-            //(Card::Seven, Card::J) => Ordering::Less,
-            (Card::Seven, Card::J) => Ordering::Greater,
-            (Card::Seven, Card::T) => Ordering::Less,
-            (Card::Seven, Card::Nine) => Ordering::Less,
-            (Card::Seven, Card::Eight) => Ordering::Less,
-            (Card::Seven, Card::Seven) => Ordering::Equal,
-            (Card::Seven, Card::Six) => Ordering::Greater,
-            (Card::Seven, Card::Five) => Ordering::Greater,
-            (Card::Seven, Card::Four) => Ordering::Greater,
-            (Card::Seven, Card::Three) => Ordering::Greater,
-            (Card::Seven, Card::Two) => Ordering::Greater,
-            (Card::Six, Card::A) => Ordering::Less,
-            (Card::Six, Card::K) => Ordering::Less,
-            (Card::Six, Card::Q) => Ordering::Less,
-            //(Card::Six, Card::J) => Ordering::Less,
-            (Card::Six, Card::J) => Ordering::Greater,
-            (Card::Six, Card::T) => Ordering::Less,
-            (Card::Six, Card::Nine) => Ordering::Less,
-            (Card::Six, Card::Eight) => Ordering::Less,
-            (Card::Six, Card::Seven) => Ordering::Less,
-            (Card::Six, Card::Six) => Ordering::Equal,
-            (Card::Six, Card::Five) => Ordering::Greater,
-            (Card::Six, Card::Four) => Ordering::Greater,
-            (Card::Six, Card::Three) => Ordering::Greater,
-            (Card::Six, Card::Two) => Ordering::Greater,
-            (Card::Five, Card::A) => Ordering::Less,
-            (Card::Five, Card::K) => Ordering::Less,
-            (Card::Five, Card::Q) => Ordering::Less,
-            //(Card::Five, Card::J) => Ordering::Less,
-            (Card::Five, Card::J) => Ordering::Greater,
-            (Card::Five, Card::T) => Ordering::Less,
-            (Card::Five, Card::Nine) => Ordering::Less,
-            (Card::Five, Card::Eight) => Ordering::Less,
-            (Card::Five, Card::Seven) => Ordering::Less,
-            (Card::Five, Card::Six) => Ordering::Less,
-            (Card::Five, Card::Five) => Ordering::Equal,
-            (Card::Five, Card::Four) => Ordering::Greater,
-            (Card::Five, Card::Three) => Ordering::Greater,
-            (Card::Five, Card::Two) => Ordering::Greater,
-            (Card::Four, Card::A) => Ordering::Less,
-            (Card::Four, Card::K) => Ordering::Less,
-            (Card::Four, Card::Q) => Ordering::Less,
-            //(Card::Four, Card::J) => Ordering::Less,
-            (Card::Four, Card::J) => Ordering::Greater,
-            (Card::Four, Card::T) => Ordering::Less,
-            (Card::Four, Card::Nine) => Ordering::Less,
-            (Card::Four, Card::Eight) => Ordering::Less,
-            (Card::Four, Card::Seven) => Ordering::Less,
-            (Card::Four, Card::Six) => Ordering::Less,
-            (Card::Four, Card::Five) => Ordering::Less,
-            (Card::Four, Card::Four) => Ordering::Equal,
-            (Card::Four, Card::Three) => Ordering::Greater,
-            (Card::Four, Card::Two) => Ordering::Greater,
-            (Card::Three, Card::A) => Ordering::Less,
-            (Card::Three, Card::K) => Ordering::Less,
-            (Card::Three, Card::Q) => Ordering::Less,
-            //(Card::Three, Card::J) => Ordering::Less,
-            (Card::Three, Card::J) => Ordering::Greater,
-            (Card::Three, Card::T) => Ordering::Less,
-            (Card::Three, Card::Nine) => Ordering::Less,
-            (Card::Three, Card::Eight) => Ordering::Less,
-            (Card::Three, Card::Seven) => Ordering::Less,
-            (Card::Three, Card::Six) => Ordering::Less,
-            (Card::Three, Card::Five) => Ordering::Less,
-            (Card::Three, Card::Four) => Ordering::Less,
-            (Card::Three, Card::Three) => Ordering::Equal,
-            (Card::Three, Card::Two) => Ordering::Greater,
-            (Card::Two, Card::A) => Ordering::Less,
-            (Card::Two, Card::K) => Ordering::Less,
-            (Card::Two, Card::Q) => Ordering::Less,
-            //(Card::Two, Card::J) => Ordering::Less,
-            (Card::Two, Card::J) => Ordering::Greater,
-            (Card::Two, Card::T) => Ordering::Less,
-            (Card::Two, Card::Nine) => Ordering::Less,
-            (Card::Two, Card::Eight) => Ordering::Less,
-            (Card::Two, Card::Seven) => Ordering::Less,
-            (Card::Two, Card::Six) => Ordering::Less,
-            (Card::Two, Card::Five) => Ordering::Less,
-            (Card::Two, Card::Four) => Ordering::Less,
-            (Card::Two, Card::Three) => Ordering::Less,
-            (Card::Two, Card::Two) => Ordering::Equal,
+        self.get_value().cmp(&other.get_value())
+    }
+}
+
+// which rules the hands are played with, since part 2 changes what J means. the order is only so
+// that hands played with different rules never compare as equal
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub enum Rules {
+    // J is a jack
+    #[default]
+    Standard,
+    // J is a joker, which counts as whatever makes the hand strongest but is the weakest card
+    // when hands of the same type are compared
+    Jokers,
+    // the same as jokers, but for any set of cards
+    Wild(Wildcards),
+}
+
+impl Rules {
+    pub fn wildcards(&self) -> Wildcards {
+        match self {
+            Self::Standard => Wildcards::default(),
+            Self::Jokers => Wildcards::new(&[Card::J]),
+            Self::Wild(wildcards) => *wildcards,
+        }
+    }
+
+    // wildcards are weaker than every other card, but keep their order between themselves
+    pub fn card_value(&self, card: Card) -> u32 {
+        let wildcards = self.wildcards();
+        if wildcards.is_empty() {
+            card.get_value()
+        } else if wildcards.contains(card) {
+            card.get_value() - 1
+        } else {
+            card.get_value() + 13
         }
     }
+
+    pub fn hand_type(&self, cards: &[Card]) -> Result<HandType, Error> {
+        HandType::with_wildcards(cards, self.wildcards())
+    }
 }
 
-#[derive(Debug, PartialOrd, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Hand {
     pub cards: Vec<Card>,
     pub hand_type: HandType,
     pub bid: u64,
+    // how the hand was typed, which is also how it is ordered
+    pub rules: Rules,
 }
 
 impl Hand {
-    pub fn from(cards: Vec<Card>, bid: u64) -> Result<Hand, &'static str> {
+    pub fn new(cards: Vec<Card>, bid: u64, rules: Rules) -> Result<Hand, Error> {
         Ok(Hand {
-            hand_type: HandType::get_hand_type(&cards).unwrap(),
-            cards: cards,
-            bid: bid,
+            hand_type: rules.hand_type(&cards)?,
+            cards,
+            bid,
+            rules,
         })
     }
 
-    pub fn from2(cards: Vec<Card>, bid: u64) -> Result<Hand, &'static str> {
-        Ok(Hand {
-            hand_type: HandType::apply_joker_rule(&cards).unwrap(),
-            cards: cards,
-            bid: bid,
-        })
+    pub fn from(cards: Vec<Card>, bid: u64) -> Result<Hand, Error> {
+        Hand::new(cards, bid, Rules::Standard)
     }
 
-    pub fn from_string(input: &str) -> Result<Hand, &'static str> {
-        let (hand, bid) = input.split_once(' ').unwrap();
-        let bid = u64::from_str_radix(bid, 10).unwrap();
-        let mut cards: Vec<Card> = vec![];
-        for c in hand.chars() {
-            let card = Card::parse(c).unwrap();
-            cards.push(card);
-        }
-        if cards.len() != 5 {
-            return Err("Hand contained invalid number of cards");
-        }
-        Hand::from(cards, bid)
+    pub fn from2(cards: Vec<Card>, bid: u64) -> Result<Hand, Error> {
+        Hand::new(cards, bid, Rules::Jokers)
     }
 
-    pub fn from_string2(input: &str) -> Result<Hand, &'static str> {
-        let (hand, bid) = input.split_once(' ').unwrap();
-        let bid = u64::from_str_radix(bid, 10).unwrap();
-        let mut cards: Vec<Card> = vec![];
-        for c in hand.chars() {
-            let card = Card::parse(c).unwrap();
-            cards.push(card);
-        }
-        if cards.len() != 5 {
-            return Err("Hand contained invalid number of cards");
-        }
-        Hand::from2(cards, bid)
+    pub fn from_string(input: &str) -> Result<Hand, Error> {
+        Hand::from_string_with(input, Rules::Standard)
     }
-}
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
+    pub fn from_string2(input: &str) -> Result<Hand, Error> {
+        Hand::from_string_with(input, Rules::Jokers)
+    }
+
+    pub fn from_string_with(input: &str, rules: Rules) -> Result<Hand, Error> {
+        Hand::from_string_sized(input, rules, HAND_SIZE)
+    }
+
+    // for hands that don't have five cards
+    pub fn from_string_sized(input: &str, rules: Rules, size: usize) -> Result<Hand, Error> {
+        let (cards, bid) = Self::parse_cards_and_bid(input, size)?;
+        Hand::new(cards, bid, rules)
+    }
+
+    // the same hand played with different rules
+    pub fn with_rules(&self, rules: Rules) -> Result<Hand, Error> {
+        Hand::new(self.cards.clone(), self.bid, rules)
+    }
+
+    // which of two hands played with the same rules wins, ignoring their bids. this is what
+    // hands are ranked by, so hands that tie come out equal
+    pub fn strength_cmp(&self, other: &Hand) -> Ordering {
+        debug_assert_eq!(self.rules, other.rules, "hands have different rules");
         match self.hand_type.cmp(&other.hand_type) {
             Ordering::Greater => return Ordering::Greater,
             Ordering::Less => return Ordering::Less,
             Ordering::Equal => {
                 for (a, b) in zip(&self.cards, &other.cards) {
-                    match a.cmp(&b) {
+                    let value = |card: &Card| self.rules.card_value(*card);
+                    match value(a).cmp(&value(b)) {
                         Ordering::Greater => return Ordering::Greater,
                         Ordering::Less => return Ordering::Less,
                         Ordering::Equal => continue,
@@ -539,49 +443,184 @@ impl Ord for Hand {
                 }
             }
         }
-        Ordering::Equal
+        // hands of the same type always have the same number of cards, but this makes sure a
+        // hand never ties with one that only has its first few cards
+        self.cards.len().cmp(&other.cards.len())
+    }
+
+    fn parse_cards_and_bid(input: &str, size: usize) -> Result<(Vec<Card>, u64), Error> {
+        let input = input.trim();
+        let (hand, bid) = input
+            .split_once(' ')
+            .ok_or_else(|| Error::MissingBid(Span::of(input, input)))?;
+        let bid = bid.trim();
+        let bid = bid
+            .parse::<u64>()
+            .map_err(|_| Error::InvalidBid(Span::of(input, bid)))?;
+        let mut cards: Vec<Card> = vec![];
+        for (i, c) in hand.char_indices() {
+            let card = Card::parse(c)
+                .ok_or_else(|| Error::InvalidCard(Span::of(input, &hand[i..i + c.len_utf8()])))?;
+            cards.push(card);
+        }
+        if cards.len() != size {
+            return Err(Error::WrongHandSize(Span::of(input, hand)));
+        }
+        Ok((cards, bid))
     }
 }
 
-pub fn rank_hands(input: &str) -> BTreeSet<Hand> {
-    let mut hands: BTreeSet<Hand> = BTreeSet::new();
-    if let Ok(lines) = read_lines(input) {
-        for line in lines {
-            if let Ok(l) = line {
-                hands.insert(Hand::from_string(&l).unwrap());
-            }
+// the same as a line of the input
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for card in &self.cards {
+            write!(f, "{card}")?;
         }
+        write!(f, " {}", self.bid)
     }
-    hands
 }
 
-pub fn rank_hands2(input: &str) -> BTreeSet<Hand> {
-    let mut hands: BTreeSet<Hand> = BTreeSet::new();
-    if let Ok(lines) = read_lines(input) {
-        for line in lines {
-            if let Ok(l) = line {
-                hands.insert(Hand::from_string2(&l).unwrap());
-            }
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// hands with the same rules go by strength_cmp, and then by bid so that only equal hands are
+// ordered the same
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rules
+            .cmp(&other.rules)
+            .then_with(|| self.strength_cmp(other))
+            .then(self.bid.cmp(&other.bid))
+    }
+}
+
+pub fn parse_hands<R: BufRead>(reader: R) -> Result<Vec<Hand>, Error> {
+    parse_hands_sized(reader, HAND_SIZE)
+}
+
+// every hand has to have size cards
+pub fn parse_hands_sized<R: BufRead>(reader: R, size: usize) -> Result<Vec<Hand>, Error> {
+    read_lines(reader)?
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            Hand::from_string_sized(l, Rules::Standard, size).map_err(|e| e.on_line(i + 1))
+        })
+        .collect()
+}
+
+pub fn rank_hands(hands: &[Hand]) -> Result<Ranking, Error> {
+    rank_hands_with(hands, Rules::Standard)
+}
+
+// hands are parsed using the standard rules, so this works out their types again with the given
+// rules before ranking them
+pub fn rank_hands_with(hands: &[Hand], rules: Rules) -> Result<Ranking, Error> {
+    rank(hands, rules, TiePolicy::default())
+}
+
+pub fn rank_hands2(hands: &[Hand]) -> Result<Ranking, Error> {
+    rank_hands_with(hands, Rules::Jokers)
+}
+
+// what to do with hands that have the same cards, and so can't be ordered
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TiePolicy {
+    // tied hands all get the lowest of their ranks, and the next hand skips past the rest of them
+    #[default]
+    SharedRank,
+    // the hand that comes first in the input is ranked lower
+    InputOrder,
+    // ranking fails
+    Error,
+}
+
+// a group of hands with the same cards
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Tie {
+    pub cards: Vec<Card>,
+    // the lowest rank the tied hands got
+    pub rank: u64,
+    // where each hand is in the input, counted from 1
+    pub hands: Vec<usize>,
+}
+
+impl fmt::Display for Tie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for card in &self.cards {
+            write!(f, "{card}")?;
         }
+        let hands: Vec<String> = self.hands.iter().map(|h| h.to_string()).collect();
+        write!(f, " at rank {} for hands {}", self.rank, hands.join(", "))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RankedHand {
+    pub rank: u64,
+    // where the hand is in the input, counted from 1
+    pub position: usize,
+    pub hand: Hand,
+}
+
+// every hand in order from weakest to strongest, along with any ties that were found
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Ranking {
+    pub hands: Vec<RankedHand>,
+    pub ties: Vec<Tie>,
+}
+
+impl Ranking {
+    pub fn total_winnings(&self) -> u64 {
+        self.hands.iter().map(|h| h.rank * h.hand.bid).sum()
     }
-    hands
 }
 
-pub fn calculate_total_winnings(hands: &BTreeSet<Hand>) -> u64 {
-    let mut total_winnings: u64 = 0;
-    for (i, h) in hands.iter().enumerate() {
-        total_winnings += (i as u64 + 1) * h.bid;
+// ranks every hand with the given rules, keeping hands that tie
+pub fn rank(hands: &[Hand], rules: Rules, policy: TiePolicy) -> Result<Ranking, Error> {
+    let mut ordered: Vec<(usize, Hand)> = hands
+        .iter()
+        .enumerate()
+        .map(|(i, h)| Ok((i + 1, h.with_rules(rules)?)))
+        .collect::<Result<_, Error>>()?;
+    // the sort is stable, so tied hands stay in input order
+    ordered.sort_by(|a, b| a.1.strength_cmp(&b.1));
+
+    let mut ranking = Ranking::default();
+    for group in ordered.chunk_by(|a, b| a.1.strength_cmp(&b.1) == Ordering::Equal) {
+        let lowest = ranking.hands.len() as u64 + 1;
+        if group.len() > 1 {
+            let tie = Tie {
+                cards: group[0].1.cards.clone(),
+                rank: lowest,
+                hands: group.iter().map(|(position, _)| *position).collect(),
+            };
+            if policy == TiePolicy::Error {
+                return Err(Error::TiedHands(tie));
+            }
+            ranking.ties.push(tie);
+        }
+        for (i, (position, hand)) in group.iter().enumerate() {
+            let rank = match policy {
+                TiePolicy::InputOrder => lowest + i as u64,
+                _ => lowest,
+            };
+            ranking.hands.push(RankedHand {
+                rank,
+                position: *position,
+                hand: hand.clone(),
+            });
+        }
     }
-    total_winnings
+    Ok(ranking)
 }
 
-// shamelessly stolen from Rust By Example
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+pub fn calculate_total_winnings(ranking: &Ranking) -> u64 {
+    ranking.total_winnings()
 }
 
 #[cfg(test)]
@@ -604,6 +643,7 @@ mod tests {
                 cards: vec![Card::A, Card::A, Card::A, Card::A, Card::A],
                 hand_type: HandType::FiveOfAKind,
                 bid: 345,
+                rules: Rules::Standard,
             }),
             Hand::from_string(input[0])
         );
@@ -612,6 +652,7 @@ mod tests {
                 cards: vec![Card::Four, Card::Four, Card::Four, Card::Seven, Card::Four],
                 hand_type: HandType::FourOfAKind,
                 bid: 1420,
+                rules: Rules::Standard,
             }),
             Hand::from_string(input[1])
         );
@@ -620,6 +661,7 @@ mod tests {
                 cards: vec![Card::Q, Card::Q, Card::Q, Card::J, Card::A],
                 hand_type: HandType::ThreeOfAKind,
                 bid: 483,
+                rules: Rules::Standard,
             }),
             Hand::from_string(input[2])
         );
@@ -628,6 +670,7 @@ mod tests {
                 cards: vec![Card::Nine, Card::Three, Card::Three, Card::Two, Card::T],
                 hand_type: HandType::OnePair,
                 bid: 10,
+                rules: Rules::Standard,
             }),
             Hand::from_string(input[3])
         );
@@ -636,6 +679,7 @@ mod tests {
                 cards: vec![Card::Eight, Card::Eight, Card::K, Card::K, Card::K],
                 hand_type: HandType::FullHouse,
                 bid: 99,
+                rules: Rules::Standard,
             }),
             Hand::from_string(input[4])
         );
@@ -644,6 +688,7 @@ mod tests {
                 cards: vec![Card::Seven, Card::T, Card::Two, Card::Five, Card::Q],
                 hand_type: HandType::HighCard,
                 bid: 1,
+                rules: Rules::Standard,
             }),
             Hand::from_string(input[5])
         );
@@ -652,9 +697,18 @@ mod tests {
                 cards: vec![Card::Three, Card::A, Card::A, Card::Six, Card::Six],
                 hand_type: HandType::TwoPair,
                 bid: 27,
+                rules: Rules::Standard,
             }),
             Hand::from_string(input[6])
         );
+        // the same cards with different bids tie, but aren't the same hand
+        let (high, low) = (
+            Hand::from_string("KK677 28").unwrap(),
+            Hand::from_string("KK677 10").unwrap(),
+        );
+        assert_ne!(high, low);
+        assert_eq!(Ordering::Equal, high.strength_cmp(&low));
+        assert_eq!(Ordering::Greater, high.cmp(&low));
     }
 
     #[test]
@@ -703,77 +757,252 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rules() {
+        assert!(Card::J > Card::T);
+        assert_eq!(11, Rules::Standard.card_value(Card::J));
+        assert!(Rules::Jokers.card_value(Card::J) < Rules::Jokers.card_value(Card::Two));
+
+        // the same type with either rules, but J only beats 9 when it is a jack
+        let jack = Hand::from_string("J2345 1").unwrap();
+        let nine = Hand::from_string("92345 2").unwrap();
+        assert!(jack > nine);
+        let joker = jack.with_rules(Rules::Jokers).unwrap();
+        assert_eq!(HandType::OnePair, joker.hand_type);
+        assert_eq!(Ok(joker.clone()), Hand::from_string2("J2345 1"));
+        // the joker makes a pair out of what was high card
+        assert!(joker > nine.with_rules(Rules::Jokers).unwrap());
+        let joker_nine = Hand::from_string2("J2399 2").unwrap();
+        let nine_joker = Hand::from_string2("92J39 3").unwrap();
+        assert_eq!(joker_nine.hand_type, nine_joker.hand_type);
+        assert!(joker_nine < nine_joker);
+        // the same hand played with different rules is never equal to itself
+        assert_ne!(Ordering::Equal, jack.cmp(&joker));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let wild = Rules::Wild(Wildcards::new(&[Card::J, Card::Two]));
+        let cards = |hand: &str| -> Vec<Card> { hand.chars().filter_map(Card::parse).collect() };
+        assert_eq!(Ok(HandType::FiveOfAKind), wild.hand_type(&cards("J2AA2")));
+        assert_eq!(Ok(HandType::FourOfAKind), wild.hand_type(&cards("3J2AA")));
+        assert_eq!(Ok(HandType::TwoPair), wild.hand_type(&cards("33AAK")));
+        assert_eq!(Ok(HandType::FiveOfAKind), wild.hand_type(&cards("JJJ22")));
+        // a joker is only wild with the joker rules
+        assert_eq!(
+            Ok(HandType::ThreeOfAKind),
+            Rules::Jokers.hand_type(&cards("AJ2K2"))
+        );
+        assert_eq!(
+            Ok(HandType::OnePair),
+            Rules::Standard.hand_type(&cards("AJ2K2"))
+        );
+
+        // wildcards are weaker than anything else, but 2 still loses to J
+        assert!(wild.card_value(Card::Two) < wild.card_value(Card::J));
+        assert!(wild.card_value(Card::J) < wild.card_value(Card::Three));
+
+        // other hand sizes are typed by how many of each card they have
+        assert_eq!(
+            Ok(HandType::Counts(vec![4, 2])),
+            Rules::Jokers.hand_type(&cards("AAJKKA"))
+        );
+        assert_eq!(
+            Ok(HandType::Counts(vec![3, 3])),
+            Rules::Standard.hand_type(&cards("AAAKKK"))
+        );
+        assert!(HandType::Counts(vec![4, 2]) > HandType::Counts(vec![3, 3]));
+        assert!(HandType::Counts(vec![3, 3]) > HandType::Counts(vec![3, 2, 1]));
+        assert!(HandType::FiveOfAKind > HandType::FourOfAKind);
+        assert!(HandType::FullHouse > HandType::ThreeOfAKind);
+        // the same counts are the same type however they were made
+        assert_eq!(HandType::FiveOfAKind, HandType::Counts(vec![5]));
+        assert_eq!(
+            Ordering::Equal,
+            HandType::Counts(vec![3, 2]).cmp(&HandType::FullHouse)
+        );
+
+        let hands = parse_hands_sized("AAAKKK 2\nAAAAK2 1\n".as_bytes(), 6).unwrap();
+        let ranking = rank(&hands, Rules::Standard, TiePolicy::Error).unwrap();
+        // four of a kind beats two threes of a kind
+        assert_eq!(2 + 2, ranking.total_winnings());
+        assert_eq!(
+            Err(Error::WrongHandSize(Span::new(1, 1, "AAAAA"))),
+            parse_hands_sized("AAAAA 1".as_bytes(), 6)
+        );
+    }
+
+    #[test]
+    fn test_part1_and_part2() {
+        let hands = Day07::parse(aoc_common::open("test.txt").unwrap()).unwrap();
+        assert_eq!(Ok(6440), Day07::part1(&hands));
+        assert_eq!(Ok(5905), Day07::part2(&hands));
+    }
+
     #[test]
     fn test_rank_hands() {
-        let input = "test.txt";
+        let input = aoc_common::open("test.txt").unwrap();
 
-        let mut expected: BTreeSet<Hand> = BTreeSet::new();
-        let hands = vec![
+        let expected = [
             Hand {
                 cards: vec![Card::Three, Card::Two, Card::T, Card::Three, Card::K],
                 hand_type: HandType::OnePair,
                 bid: 765,
+                rules: Rules::Standard,
             },
             Hand {
                 cards: vec![Card::K, Card::T, Card::J, Card::J, Card::T],
                 hand_type: HandType::TwoPair,
                 bid: 220,
+                rules: Rules::Standard,
             },
             Hand {
                 cards: vec![Card::K, Card::K, Card::Six, Card::Seven, Card::Seven],
                 hand_type: HandType::TwoPair,
                 bid: 28,
+                rules: Rules::Standard,
             },
             Hand {
                 cards: vec![Card::T, Card::Five, Card::Five, Card::J, Card::Five],
                 hand_type: HandType::ThreeOfAKind,
                 bid: 684,
+                rules: Rules::Standard,
             },
             Hand {
                 cards: vec![Card::Q, Card::Q, Card::Q, Card::J, Card::A],
                 hand_type: HandType::ThreeOfAKind,
                 bid: 483,
+                rules: Rules::Standard,
             },
         ];
-        for h in hands {
-            expected.insert(h);
-        }
-        assert_eq!(expected, rank_hands(input));
+        let ranking = rank_hands(&parse_hands(input).unwrap()).unwrap();
+        assert_eq!(
+            vec![1, 2, 3, 4, 5],
+            ranking.hands.iter().map(|h| h.rank).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            expected.to_vec(),
+            ranking
+                .hands
+                .iter()
+                .map(|h| h.hand.clone())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(Error::InvalidCard(Span::new(1, 3, "X"))),
+            Hand::from_string("AAXAA 345")
+        );
+        assert_eq!(
+            Err(Error::InvalidBid(Span::new(1, 7, "3x5"))),
+            Hand::from_string("AAAAA 3x5")
+        );
+        assert_eq!(
+            Err(Error::MissingBid(Span::new(1, 1, "AAAAA"))),
+            Hand::from_string("AAAAA")
+        );
+        assert_eq!(
+            Err(Error::WrongHandSize(Span::new(1, 1, "AAAA"))),
+            Hand::from_string("AAAA 345")
+        );
+        assert_eq!(
+            Ok(HandType::Counts(vec![3])),
+            HandType::apply_joker_rule(&[Card::J, Card::A, Card::A])
+        );
+        assert_eq!(
+            Err(Error::InvalidHandSize(0)),
+            HandType::apply_joker_rule(&[])
+        );
+        assert_eq!(
+            Err(Error::InvalidCard(Span::new(3, 5, "1"))),
+            parse_hands("32T3K 765\nT55J5 684\nKK671 28\n".as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_ties() {
+        let hands = parse_hands("KK677 28\n32T3K 765\nKK677 10\n".as_bytes()).unwrap();
+        // hands that are exactly the same are both kept
+        let same = parse_hands("KK677 28\n32T3K 765\nKK677 28\n".as_bytes()).unwrap();
+        assert_eq!(
+            765 + 2 * 28 + 2 * 28,
+            rank_hands(&same).unwrap().total_winnings()
+        );
+
+        let ranking = rank(&hands, Rules::Standard, TiePolicy::SharedRank).unwrap();
+        assert_eq!(
+            vec![(1, 2), (2, 1), (2, 3)],
+            ranking
+                .hands
+                .iter()
+                .map(|h| (h.rank, h.position))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(765 + 2 * 28 + 2 * 10, ranking.total_winnings());
+        let tie = Tie {
+            cards: hands[0].cards.clone(),
+            rank: 2,
+            hands: vec![1, 3],
+        };
+        assert_eq!(vec![tie.clone()], ranking.ties);
+        assert_eq!("KK677 at rank 2 for hands 1, 3", tie.to_string());
+
+        let ranking = rank(&hands, Rules::Standard, TiePolicy::InputOrder).unwrap();
+        assert_eq!(765 + 2 * 28 + 3 * 10, ranking.total_winnings());
+        assert_eq!(1, ranking.ties.len());
+        assert_eq!(
+            Err(Error::TiedHands(tie)),
+            rank(&hands, Rules::Standard, TiePolicy::Error)
+        );
+
+        // different cards are never tied, even if jokers make them the same type
+        let hands = parse_hands("2J345 1\nJ2345 2".as_bytes()).unwrap();
+        let ranking = rank(&hands, Rules::Jokers, TiePolicy::Error).unwrap();
+        assert!(ranking.ties.is_empty());
+        assert_eq!("2J345 1", ranking.hands[1].hand.to_string());
     }
 
     #[test]
     fn test_calculate_total_winnings() {
-        let mut input: BTreeSet<Hand> = BTreeSet::new();
         let hands = vec![
             Hand {
                 cards: vec![Card::Three, Card::Two, Card::T, Card::Three, Card::K],
                 hand_type: HandType::OnePair,
                 bid: 765,
+                rules: Rules::Standard,
             },
             Hand {
                 cards: vec![Card::K, Card::T, Card::J, Card::J, Card::T],
                 hand_type: HandType::TwoPair,
                 bid: 220,
+                rules: Rules::Standard,
             },
             Hand {
                 cards: vec![Card::K, Card::K, Card::Six, Card::Seven, Card::Seven],
                 hand_type: HandType::TwoPair,
                 bid: 28,
+                rules: Rules::Standard,
             },
             Hand {
                 cards: vec![Card::Q, Card::Q, Card::Q, Card::J, Card::A],
                 hand_type: HandType::ThreeOfAKind,
                 bid: 483,
+                rules: Rules::Standard,
             },
             Hand {
                 cards: vec![Card::T, Card::Five, Card::Five, Card::J, Card::Five],
                 hand_type: HandType::ThreeOfAKind,
                 bid: 684,
+                rules: Rules::Standard,
             },
         ];
-        for h in hands {
-            input.insert(h);
-        }
-        assert_eq!(6440, calculate_total_winnings(&input));
+        assert_eq!(6440, calculate_total_winnings(&rank_hands(&hands).unwrap()));
+        assert_eq!(
+            5905,
+            calculate_total_winnings(&rank_hands2(&hands).unwrap())
+        );
     }
 }
@@ -0,0 +1,326 @@
+use std::{cmp::Ordering, collections::HashSet, fmt, iter::zip};
+
+use aoc_common::Span;
+
+use crate::{Card, Error, HandType};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Suit::Clubs => 'c',
+            Suit::Diamonds => 'd',
+            Suit::Hearts => 'h',
+            Suit::Spades => 's',
+        }
+    }
+
+    pub fn parse(c: char) -> Option<Suit> {
+        match c {
+            'c' => Some(Suit::Clubs),
+            'd' => Some(Suit::Diamonds),
+            'h' => Some(Suit::Hearts),
+            's' => Some(Suit::Spades),
+            _ => None,
+        }
+    }
+}
+
+// a card from a standard deck, where the rank is one of the camel cards
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct PokerCard {
+    pub rank: Card,
+    pub suit: Suit,
+}
+
+impl PokerCard {
+    pub fn new(rank: Card, suit: Suit) -> Self {
+        Self { rank, suit }
+    }
+
+    // all 52 cards, from the twos up to the aces
+    pub fn deck() -> Vec<PokerCard> {
+        Card::ALL
+            .iter()
+            .flat_map(|r| Suit::ALL.iter().map(|s| PokerCard::new(*r, *s)))
+            .collect()
+    }
+}
+
+// e.g. "As" for the ace of spades
+impl fmt::Display for PokerCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit.symbol())
+    }
+}
+
+// parses cards written like "AsKd", with or without spaces between them. the same card can't
+// be in the list twice
+pub fn parse_cards(input: &str) -> Result<Vec<PokerCard>, Error> {
+    let mut cards: Vec<PokerCard> = vec![];
+    let mut seen: HashSet<PokerCard> = HashSet::new();
+    for part in input.split_whitespace() {
+        let mut chars = part.char_indices().peekable();
+        while let Some((i, rank)) = chars.next() {
+            let end = chars.peek().map_or(part.len(), |(j, c)| j + c.len_utf8());
+            let text = &part[i..end];
+            let suit = chars.next().map(|(_, c)| c);
+            let card = match (Card::parse(rank), suit.and_then(Suit::parse)) {
+                (Some(rank), Some(suit)) => PokerCard::new(rank, suit),
+                _ => return Err(Error::InvalidCard(Span::of(input, text))),
+            };
+            if !seen.insert(card) {
+                return Err(Error::DuplicateCard(Span::of(input, text)));
+            }
+            cards.push(card);
+        }
+    }
+    Ok(cards)
+}
+
+// poker hand categories from weakest to strongest
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Category::HighCard => "high card",
+            Category::OnePair => "one pair",
+            Category::TwoPair => "two pair",
+            Category::ThreeOfAKind => "three of a kind",
+            Category::Straight => "straight",
+            Category::Flush => "flush",
+            Category::FullHouse => "full house",
+            Category::FourOfAKind => "four of a kind",
+            Category::StraightFlush => "straight flush",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// the best five cards from a hand and how strong they are
+#[derive(Debug, Clone)]
+pub struct PokerHand {
+    pub category: Category,
+    // the ranks that decide between hands of the same category, most important first. for
+    // straights this is only the highest card, which is a five for A2345
+    pub ranks: Vec<Card>,
+    pub cards: Vec<PokerCard>,
+}
+
+impl PokerHand {
+    // works out the category and kickers of exactly five cards
+    fn of_five(cards: &[PokerCard]) -> PokerHand {
+        let ranks: Vec<Card> = cards.iter().map(|c| c.rank).collect();
+        let counts = HandType::count_cards(&ranks);
+        // each rank once, with the ones there are more of first and then the highest first
+        let mut by_count: Vec<(u32, Card)> = counts.iter().map(|(r, n)| (*n, *r)).collect();
+        by_count.sort_by(|a, b| b.cmp(a));
+        let signature: Vec<u32> = by_count.iter().map(|(n, _)| *n).collect();
+        let kickers: Vec<Card> = by_count.iter().map(|(_, r)| *r).collect();
+
+        let flush = cards.iter().all(|c| c.suit == cards[0].suit);
+        let straight = match kickers.as_slice() {
+            [Card::A, Card::Five, Card::Four, Card::Three, Card::Two] => Some(Card::Five),
+            [high, .., low] if kickers.len() == 5 && high.get_value() - low.get_value() == 4 => {
+                Some(*high)
+            }
+            _ => None,
+        };
+        let (category, ranks) = match (straight, flush, signature.as_slice()) {
+            (Some(high), true, _) => (Category::StraightFlush, vec![high]),
+            (_, _, [4, 1]) => (Category::FourOfAKind, kickers),
+            (_, _, [3, 2]) => (Category::FullHouse, kickers),
+            (_, true, _) => (Category::Flush, kickers),
+            (Some(high), _, _) => (Category::Straight, vec![high]),
+            (_, _, [3, 1, 1]) => (Category::ThreeOfAKind, kickers),
+            (_, _, [2, 2, 1]) => (Category::TwoPair, kickers),
+            (_, _, [2, 1, 1, 1]) => (Category::OnePair, kickers),
+            _ => (Category::HighCard, kickers),
+        };
+        PokerHand {
+            category,
+            ranks,
+            cards: cards.to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for PokerHand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for card in &self.cards {
+            write!(f, "{card}")?;
+        }
+        write!(f, " ({})", self.category)
+    }
+}
+
+// hands are equal when neither beats the other, whatever suits they use
+impl PartialEq for PokerHand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PokerHand {}
+
+impl PartialOrd for PokerHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PokerHand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.category.cmp(&other.category) {
+            Ordering::Greater => return Ordering::Greater,
+            Ordering::Less => return Ordering::Less,
+            Ordering::Equal => {
+                for (a, b) in zip(&self.ranks, &other.ranks) {
+                    match a.cmp(b) {
+                        Ordering::Greater => return Ordering::Greater,
+                        Ordering::Less => return Ordering::Less,
+                        Ordering::Equal => continue,
+                    }
+                }
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+// the best five card hand that can be made from the cards, e.g. two hole cards and five on the
+// board. there have to be at least five
+pub fn evaluate(cards: &[PokerCard]) -> Result<PokerHand, Error> {
+    if cards.len() < 5 {
+        return Err(Error::InvalidHandSize(cards.len()));
+    }
+    let mut best: Option<PokerHand> = None;
+    let mut chosen: Vec<PokerCard> = Vec::with_capacity(5);
+    best_of_five(cards, &mut chosen, &mut best);
+    best.ok_or(Error::InvalidHandSize(cards.len()))
+}
+
+// tries every way of picking the rest of the five cards from what's left
+fn best_of_five(cards: &[PokerCard], chosen: &mut Vec<PokerCard>, best: &mut Option<PokerHand>) {
+    if chosen.len() == 5 {
+        let hand = PokerHand::of_five(chosen);
+        if best.as_ref().is_none_or(|b| hand > *b) {
+            *best = Some(hand);
+        }
+        return;
+    }
+    let needed = 5 - chosen.len();
+    for i in 0..=cards.len().saturating_sub(needed) {
+        chosen.push(cards[i]);
+        best_of_five(&cards[i + 1..], chosen, best);
+        chosen.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(input: &str) -> PokerHand {
+        evaluate(&parse_cards(input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_cards() {
+        assert_eq!(
+            Ok(vec![
+                PokerCard::new(Card::A, Suit::Spades),
+                PokerCard::new(Card::K, Suit::Diamonds),
+                PokerCard::new(Card::T, Suit::Clubs),
+            ]),
+            parse_cards("AsKd Tc")
+        );
+        assert_eq!(
+            Err(Error::InvalidCard(Span::new(1, 3, "Kx"))),
+            parse_cards("AsKx")
+        );
+        assert_eq!(
+            Err(Error::InvalidCard(Span::new(1, 3, "K"))),
+            parse_cards("AsK")
+        );
+        assert_eq!(
+            Err(Error::DuplicateCard(Span::new(1, 6, "As"))),
+            parse_cards("AsKd As")
+        );
+        assert_eq!(52, PokerCard::deck().len());
+        assert_eq!("2c", PokerCard::deck()[0].to_string());
+    }
+
+    #[test]
+    fn test_categories() {
+        assert_eq!(Category::StraightFlush, hand("9hThJhQhKh").category);
+        assert_eq!(Category::FourOfAKind, hand("9h9s9d9cKh").category);
+        assert_eq!(Category::FullHouse, hand("9h9s9dKcKh").category);
+        assert_eq!(Category::Flush, hand("2h9hJh4hKh").category);
+        assert_eq!(Category::Straight, hand("9hTsJdQcKh").category);
+        assert_eq!(Category::ThreeOfAKind, hand("9h9s9d2cKh").category);
+        assert_eq!(Category::TwoPair, hand("9h9s2d2cKh").category);
+        assert_eq!(Category::OnePair, hand("9h9s3d2cKh").category);
+        assert_eq!(Category::HighCard, hand("9h7s3d2cKh").category);
+
+        // the ace is low in the wheel, so it loses to a six high straight
+        let wheel = hand("Ah2s3d4c5h");
+        assert_eq!(Category::Straight, wheel.category);
+        assert_eq!(vec![Card::Five], wheel.ranks);
+        assert!(wheel < hand("2h3s4d5c6h"));
+        assert_eq!(Category::HighCard, hand("Qh Ks Ad 2c 3h").category);
+    }
+
+    #[test]
+    fn test_kickers() {
+        assert_eq!(
+            vec![Card::Nine, Card::Two, Card::K],
+            hand("9h9s2d2cKh").ranks
+        );
+        assert!(hand("9h9s2d2cKh") > hand("9d9c2h2sQh"));
+        assert!(hand("AhAs2d3c4h") > hand("KhKsQdJc9h"));
+        assert!(hand("AhAsKd3c4h") > hand("AdAcQhJs9h"));
+        // suits don't matter unless they make a flush
+        assert_eq!(hand("AhAsKd3c4h"), hand("AdAcKh3s4d"));
+        assert_eq!(Ordering::Equal, hand("AhAsKd3c4h").cmp(&hand("AdAcKh3s4d")));
+    }
+
+    #[test]
+    fn test_best_of_seven() {
+        // two hole cards and five on the board
+        let best = hand("AhKh QhJhTh 2c 3d");
+        assert_eq!(Category::StraightFlush, best.category);
+        assert_eq!(vec![Card::A], best.ranks);
+        assert_eq!("AhKhQhJhTh (straight flush)", best.to_string());
+
+        let best = hand("7s7d 7c2h2d Kh Ks");
+        assert_eq!(Category::FullHouse, best.category);
+        assert_eq!(vec![Card::Seven, Card::K], best.ranks);
+
+        assert_eq!(
+            Err(Error::InvalidHandSize(4)),
+            evaluate(&parse_cards("AsKsQsJs").unwrap())
+        );
+    }
+}
@@ -1,14 +1,24 @@
-use day_09::{parse_line, predict_next_value, read_lines};
+use std::process::ExitCode;
 
-fn main() {
-    let mut sum: i64 = 0;
-    if let Ok(lines) = read_lines("input.txt") {
-        for line in lines {
-            if let Ok(l) = line {
-                let numbers: Vec<i64> = parse_line(&l);
-                sum += predict_next_value(&numbers);
-            }
+use aoc_common::{open_from_args, Solution};
+use day_09::Day09;
+
+fn main() -> ExitCode {
+    let input = match open_from_args(env!("CARGO_MANIFEST_DIR")) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match Day09::parse(input).and_then(|histories| Day09::part1(&histories)) {
+        Ok(sum) => {
+            println!("sum of next values for all histories is: {sum}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
         }
     }
-    println!("sum of next values for all histories is: {sum}");
 }
@@ -1,19 +1,69 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
-
-// shamelessly stolen from Rust By Example
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+use std::{fmt, io::BufRead};
+
+use aoc_common::{read_lines, InputError, Solution, Span};
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u32 = 9;
+
+    type Input = Vec<Vec<i64>>;
+    type Answer = i64;
+    type Error = Error;
+
+    fn parse<R: BufRead>(reader: R) -> Result<Self::Input, Self::Error> {
+        parse_histories(reader)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        Ok(sum_next_values(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        Ok(sum_previous_values(input))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    Input(InputError),
+    InvalidNumber(Span),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(e) => write!(f, "{e}"),
+            Self::InvalidNumber(span) => write!(f, "invalid number {span}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Input(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    fn on_line(self, line: usize) -> Self {
+        match self {
+            Self::InvalidNumber(span) => Self::InvalidNumber(span.on_line(line)),
+            e => e,
+        }
+    }
 }
 
-pub fn deltas(numbers: &Vec<i64>) -> Vec<i64> {
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Self::Input(e)
+    }
+}
+
+pub fn deltas(numbers: &[i64]) -> Vec<i64> {
     numbers
         // iterate over overlapping windows of size n
         .windows(2)
@@ -21,22 +71,53 @@ pub fn deltas(numbers: &Vec<i64>) -> Vec<i64> {
         .collect()
 }
 
-pub fn predict_next_value(numbers: &Vec<i64>) -> i64 {
+pub fn predict_next_value(numbers: &[i64]) -> i64 {
+    // an empty history has nothing to predict from
+    let Some(last) = numbers.last() else {
+        return 0;
+    };
     let diffs = deltas(numbers);
     if diffs.iter().all(|&d| d == 0) {
         // if all differences are 0, return last number in list
-        return numbers[numbers.len() - 1];
+        return *last;
     }
     // add last number in list to last number of next iteration
-    numbers[numbers.len() - 1] + predict_next_value(&diffs)
+    last + predict_next_value(&diffs)
+}
+
+pub fn parse_line(line: &str) -> Result<Vec<i64>, Error> {
+    line.split_whitespace()
+        .map(|x| {
+            x.parse::<i64>()
+                .map_err(|_| Error::InvalidNumber(Span::of(line, x)))
+        })
+        .collect()
 }
 
-pub fn parse_line(line: &str) -> Vec<i64> {
-    line.split(' ')
-        .filter_map(|x| x.parse::<i64>().ok())
+pub fn parse_histories<R: BufRead>(reader: R) -> Result<Vec<Vec<i64>>, Error> {
+    read_lines(reader)?
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| parse_line(l).map_err(|e| e.on_line(i + 1)))
         .collect()
 }
 
+pub fn sum_next_values(histories: &[Vec<i64>]) -> i64 {
+    histories.iter().map(|h| predict_next_value(h)).sum()
+}
+
+pub fn sum_previous_values(histories: &[Vec<i64>]) -> i64 {
+    histories
+        .iter()
+        .map(|h| {
+            // to find previous value, just reverse the list
+            let numbers: Vec<i64> = h.iter().rev().copied().collect();
+            predict_next_value(&numbers)
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,11 +125,23 @@ mod tests {
     #[test]
     fn test_parse_line() {
         assert_eq!(
-            vec![1, 4, 5, 2, 16, 20, 304, -23, 19],
+            Ok(vec![1, 4, 5, 2, 16, 20, 304, -23, 19]),
             parse_line("1 4 5 2 16 20 304 -23 19")
         );
     }
 
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(Error::InvalidNumber(Span::new(1, 5, "-x3"))),
+            parse_line("1 4 -x3 2")
+        );
+        assert_eq!(
+            Err(Error::InvalidNumber(Span::new(2, 3, "3.5"))),
+            parse_histories("0 3 6\n1 3.5 6\n".as_bytes())
+        );
+    }
+
     #[test]
     fn test_deltas() {
         let input1 = vec![0, 3, 6, 9, 12, 15];
@@ -90,4 +183,11 @@ mod tests {
         let input4: Vec<i64> = input3.into_iter().rev().collect();
         assert_eq!(10, predict_next_value(&input4));
     }
+
+    #[test]
+    fn test_example() {
+        let histories = parse_histories(aoc_common::open("test.txt").unwrap()).unwrap();
+        assert_eq!(114, sum_next_values(&histories));
+        assert_eq!(2, sum_previous_values(&histories));
+    }
 }